@@ -0,0 +1,97 @@
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{collections::HashMap, sync::Arc};
+
+use evolut::model::creature::{
+    brain::{
+        Activation, Brain, BrainConfig, InternalNeuron, Neuron, NeuronRegistry, SensoryInputs,
+    },
+    genome::Genome,
+};
+
+/// The number of brains evaluated in each iteration, so that one unusually small or large brain does not dominate.
+const BRAINS: usize = 100;
+
+fn brains(genome_length: usize) -> Vec<Brain> {
+    let mut generator = ChaCha8Rng::seed_from_u64(0);
+    let config = BrainConfig::default();
+    let registry = NeuronRegistry::builtin();
+
+    (0..BRAINS)
+        .map(|_| {
+            Brain::new(
+                &Genome::random(genome_length, &mut generator),
+                &config,
+                &registry,
+            )
+        })
+        .collect()
+}
+
+/// Values for the built-in sensory inputs, in the order in which they are registered.
+fn sensory_inputs() -> SensoryInputs {
+    SensoryInputs::new(vec![12.5, 0.8, -0.3, 800.0, 0.1, 0.0, 0.0, 0.6, 0.9, 0.0])
+}
+
+/// Evaluates a brain as the simulation did before brains were compiled: by walking the tree, and caching the
+/// activations of internal neurons in a fresh map.
+fn evaluate_tree(brain: &Brain, sensory_inputs: &SensoryInputs) -> f32 {
+    let mut internal_activation_cache: HashMap<Arc<InternalNeuron>, f32> = HashMap::new();
+
+    brain
+        .neurons()
+        .iter()
+        .filter_map(|neuron| match neuron {
+            Neuron::Action(action_neuron) => {
+                Some(action_neuron.activation(&mut internal_activation_cache, sensory_inputs))
+            }
+            _ => None,
+        })
+        .sum()
+}
+
+fn evaluate_compiled(brain: &mut Brain, sensory_inputs: &SensoryInputs) -> f32 {
+    brain
+        .evaluate(sensory_inputs)
+        .map(|(_, activation)| activation)
+        .sum()
+}
+
+fn brain_evaluation(c: &mut Criterion) {
+    let sensory_inputs = sensory_inputs();
+    let mut group = c.benchmark_group("brain_evaluation");
+
+    for genome_length in [20, 100] {
+        let mut brains = brains(genome_length);
+
+        group.bench_with_input(
+            BenchmarkId::new("tree", genome_length),
+            &sensory_inputs,
+            |b, sensory_inputs| {
+                b.iter(|| {
+                    for brain in &brains {
+                        black_box(evaluate_tree(brain, sensory_inputs));
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("compiled", genome_length),
+            &sensory_inputs,
+            |b, sensory_inputs| {
+                b.iter(|| {
+                    for brain in &mut brains {
+                        black_box(evaluate_compiled(brain, sensory_inputs));
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, brain_evaluation);
+criterion_main!(benches);
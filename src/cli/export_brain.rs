@@ -0,0 +1,58 @@
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use std::{fs, path::PathBuf};
+
+use evolut::{
+    model::creature::brain::{Brain, NeuronRegistry},
+    simulation::SimulationConfig,
+};
+
+use super::inspect_genome::decode_genome;
+
+#[derive(Args)]
+pub struct ExportBrainArguments {
+    /// The genome, in its hex, text or base64 form.
+    genome: String,
+    /// A file to write the brain to, in the Graphviz DOT language.
+    #[arg(long, value_name = "PATH")]
+    dot: Option<PathBuf>,
+    /// A file to write the brain to, as JSON lists of nodes and edges.
+    #[arg(long, value_name = "PATH")]
+    json: Option<PathBuf>,
+    /// A TOML file to load the brain configuration from.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+pub fn export_brain(arguments: ExportBrainArguments) -> Result<()> {
+    if arguments.dot.is_none() && arguments.json.is_none() {
+        bail!("Nothing to export. Pass --dot, --json or both.");
+    }
+
+    let config = match &arguments.config {
+        Some(path) => SimulationConfig::load(path)?,
+        None => SimulationConfig::default(),
+    };
+
+    let genome = decode_genome(arguments.genome.trim()).context("Could not decode the genome.")?;
+    let registry = NeuronRegistry::builtin();
+    let graph = Brain::new(&genome, &config.brain, &registry).graph();
+
+    println!(
+        "{} neurons and {} connections in the brain.",
+        graph.nodes.len(),
+        graph.edges.len()
+    );
+
+    if let Some(path) = &arguments.dot {
+        fs::write(path, graph.to_dot())
+            .with_context(|| format!("Could not write {}.", path.display()))?;
+    }
+
+    if let Some(path) = &arguments.json {
+        fs::write(path, graph.to_json())
+            .with_context(|| format!("Could not write {}.", path.display()))?;
+    }
+
+    Ok(())
+}
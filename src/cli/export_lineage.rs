@@ -0,0 +1,46 @@
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use std::{fs, path::PathBuf};
+
+use evolut::simulation::LineageTree;
+
+#[derive(Args)]
+pub struct ExportLineageArguments {
+    /// The lineage log written by a run with --lineage.
+    log: PathBuf,
+    /// A file to write the ancestry tree to, in Newick format.
+    #[arg(long, value_name = "PATH")]
+    newick: Option<PathBuf>,
+    /// A file to write the ancestry tree to, as a CSV list of parent and child pairs.
+    #[arg(long, value_name = "PATH")]
+    edges: Option<PathBuf>,
+    /// Only exports the survivors at the end of the log, and their ancestors.
+    #[arg(long)]
+    survivors: bool,
+}
+
+pub fn export_lineage(arguments: ExportLineageArguments) -> Result<()> {
+    if arguments.newick.is_none() && arguments.edges.is_none() {
+        bail!("Nothing to export. Pass --newick, --edges or both.");
+    }
+
+    let mut tree = LineageTree::load(&arguments.log)?;
+
+    if arguments.survivors {
+        tree = tree.survivors_only();
+    }
+
+    println!("{} creatures in the tree.", tree.nodes().len());
+
+    if let Some(path) = &arguments.newick {
+        fs::write(path, tree.to_newick())
+            .with_context(|| format!("Could not write {}.", path.display()))?;
+    }
+
+    if let Some(path) = &arguments.edges {
+        fs::write(path, tree.to_edge_list())
+            .with_context(|| format!("Could not write {}.", path.display()))?;
+    }
+
+    Ok(())
+}
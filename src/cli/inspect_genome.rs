@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use std::{collections::HashSet, path::PathBuf};
+
+use evolut::{
+    model::creature::{
+        brain::{Brain, Connection, InputNeuron, Neuron, NeuronRegistry},
+        genome::Genome,
+    },
+    simulation::SimulationConfig,
+};
+
+#[derive(Args)]
+pub struct InspectGenomeArguments {
+    /// The genome, in either its text form (genome:v2:...) or its base64 form. The 22 character hex representations of
+    /// its genes, one after the other, are also accepted.
+    genome: String,
+    /// A TOML file to load the brain configuration from.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+pub fn inspect_genome(arguments: InspectGenomeArguments) -> Result<()> {
+    let config = match &arguments.config {
+        Some(path) => SimulationConfig::load(path)?,
+        None => SimulationConfig::default(),
+    };
+
+    let genome = decode_genome(arguments.genome.trim()).context("Could not decode the genome.")?;
+    let registry = NeuronRegistry::builtin();
+
+    println!("Text:   {genome}");
+    println!("Base64: {}\n", genome.to_base64());
+
+    if let Err(invalid) = genome.validate(&config.brain, &registry) {
+        println!("{invalid}\n");
+    }
+
+    let brain = Brain::new(&genome, &config.brain, &registry);
+
+    print!("{}", describe_brain(&brain));
+
+    Ok(())
+}
+
+/// Reads a genome in any of the forms it can be written in.
+pub(super) fn decode_genome(text: &str) -> Result<Genome> {
+    if text.starts_with("genome:") {
+        text.parse()
+    } else if text.chars().all(|character| character.is_ascii_hexdigit()) {
+        // The magic number at the start of the base64 form always contains letters beyond f.
+        Genome::from_hex(text)
+    } else {
+        Genome::from_base64(text)
+    }
+}
+
+/// Describes a brain as a tree for each action neuron, listing the inputs of every neuron beneath it.
+///
+/// Each neuron is followed by its activation function and bias, and each input is preceded by its weight.
+fn describe_brain(brain: &Brain) -> String {
+    let mut description = String::new();
+
+    let mut described_internal_neurons: HashSet<u8> = HashSet::new();
+
+    for neuron in brain.neurons() {
+        if let Neuron::Action(action_neuron) = neuron {
+            description.push_str(&format!(
+                "{} ({}, {:+.4})\n",
+                action_neuron.output(),
+                action_neuron.function(),
+                action_neuron.bias()
+            ));
+
+            describe_inputs(
+                action_neuron.inputs(),
+                1,
+                &mut described_internal_neurons,
+                &mut description,
+            );
+        }
+    }
+
+    if description.is_empty() {
+        description.push_str("The brain has no action neurons.\n");
+    }
+
+    description
+}
+
+fn describe_inputs(
+    inputs: &[Connection],
+    depth: usize,
+    described_internal_neurons: &mut HashSet<u8>,
+    description: &mut String,
+) {
+    for connection in inputs {
+        let indent = "    ".repeat(depth);
+        let weight = connection.weight();
+
+        match connection.input() {
+            InputNeuron::Sensory(sensory_neuron) => {
+                description.push_str(&format!(
+                    "{indent}{weight:+.4} x {}\n",
+                    sensory_neuron.input()
+                ));
+            }
+            InputNeuron::Internal(internal_neuron) => {
+                let number = internal_neuron.id() - 128;
+
+                description.push_str(&format!(
+                    "{indent}{weight:+.4} x Internal {number} ({}, {:+.4})\n",
+                    internal_neuron.function(),
+                    internal_neuron.bias()
+                ));
+
+                // Each internal neuron's inputs are only listed the first time it is found.
+                if described_internal_neurons.insert(internal_neuron.id()) {
+                    describe_inputs(
+                        internal_neuron.inputs(),
+                        depth + 1,
+                        described_internal_neurons,
+                        description,
+                    );
+                }
+            }
+            InputNeuron::Recurrent(id) => {
+                let number = id - 128;
+
+                description.push_str(&format!(
+                    "{indent}{weight:+.4} x Internal {number} (previous evaluation)\n"
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! The command-line interface of the evolut binary.
+
+mod export_brain;
+mod export_lineage;
+mod inspect_genome;
+mod replay;
+mod resume;
+mod run;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+pub use export_brain::ExportBrainArguments;
+pub use export_lineage::ExportLineageArguments;
+pub use inspect_genome::InspectGenomeArguments;
+pub use replay::ReplayArguments;
+pub use resume::ResumeArguments;
+pub use run::RunArguments;
+
+/// A simulation of the evolution of biological neural networks.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs a new simulation.
+    Run(RunArguments),
+    /// Decodes an encoded genome and prints the brain it builds.
+    InspectGenome(InspectGenomeArguments),
+    /// Resumes a simulation from a saved snapshot.
+    Replay(ReplayArguments),
+    /// Resumes a simulation from the most recent valid checkpoint in a directory.
+    Resume(ResumeArguments),
+    /// Writes the ancestry tree recorded in a lineage log as Newick or an edge list.
+    ExportLineage(ExportLineageArguments),
+    /// Decodes an encoded genome and writes the brain it builds as Graphviz DOT, JSON or both.
+    ExportBrain(ExportBrainArguments),
+}
+
+impl Cli {
+    /// Runs the chosen subcommand.
+    pub fn execute(self) -> Result<()> {
+        match self.command {
+            Command::Run(arguments) => run::run(arguments),
+            Command::InspectGenome(arguments) => inspect_genome::inspect_genome(arguments),
+            Command::Replay(arguments) => replay::replay(arguments),
+            Command::Resume(arguments) => resume::resume(arguments),
+            Command::ExportLineage(arguments) => export_lineage::export_lineage(arguments),
+            Command::ExportBrain(arguments) => export_brain::export_brain(arguments),
+        }
+    }
+}
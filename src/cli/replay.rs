@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use evolut::simulation::{SimulationConfig, Snapshot};
+
+use super::run::{SimulationArguments, simulation_app};
+
+#[derive(Args)]
+pub struct ReplayArguments {
+    /// The snapshot to resume the simulation from.
+    snapshot: PathBuf,
+    /// A TOML file to load the simulation configuration from, replacing the one stored in the snapshot.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    #[command(flatten)]
+    simulation: SimulationArguments,
+}
+
+pub fn replay(arguments: ReplayArguments) -> Result<()> {
+    let snapshot = Snapshot::load(&arguments.snapshot)?;
+
+    resume_from(snapshot, arguments.config.as_deref(), &arguments.simulation)
+}
+
+/// Runs a simulation which carries on from a snapshot, optionally with a different configuration.
+pub fn resume_from(
+    snapshot: Snapshot,
+    config: Option<&Path>,
+    arguments: &SimulationArguments,
+) -> Result<()> {
+    let config = match config {
+        Some(path) => SimulationConfig::load(path)?,
+        None => snapshot.config().clone(),
+    };
+
+    let rng = snapshot.rng();
+    let mut app = simulation_app(arguments, config, rng)?;
+
+    snapshot.restore(app.world_mut());
+
+    app.run();
+
+    Ok(())
+}
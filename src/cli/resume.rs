@@ -0,0 +1,44 @@
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+use evolut::simulation::latest_checkpoint;
+
+use super::{replay::resume_from, run::SimulationArguments};
+
+#[derive(Args)]
+pub struct ResumeArguments {
+    /// The checkpoint directory to resume from. If checkpoints are being saved, and no other directory is given, they
+    /// are saved here too.
+    directory: PathBuf,
+    /// A TOML file to load the simulation configuration from, replacing the one stored in the checkpoint.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    #[command(flatten)]
+    simulation: SimulationArguments,
+}
+
+pub fn resume(mut arguments: ResumeArguments) -> Result<()> {
+    let latest = latest_checkpoint(&arguments.directory)?;
+
+    // Logging is only set up once the app is built, so this is the only place these can be reported.
+    for skipped in &latest.skipped {
+        eprintln!("{skipped}");
+    }
+
+    println!("Resuming from {}", latest.path.display());
+
+    let checkpoints = &mut arguments.simulation.checkpoints;
+
+    if checkpoints.has_trigger() {
+        checkpoints
+            .checkpoint_dir
+            .get_or_insert(arguments.directory);
+    }
+
+    resume_from(
+        latest.snapshot,
+        arguments.config.as_deref(),
+        &arguments.simulation,
+    )
+}
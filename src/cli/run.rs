@@ -0,0 +1,268 @@
+use anyhow::{Context, Result, bail};
+use bevy::{log::LogPlugin, prelude::*};
+use clap::Args;
+use std::{
+    fs::{self, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use evolut::simulation::{
+    AppearancePlugin, CheckpointPlugin, CreaturePlugin, FoodPlugin, HeadlessPlugin, LineagePlugin,
+    SetupPlugin, SimulationConfig, SimulationRng, SnapshotOnExitPlugin, SpatialIndexPlugin,
+    SpeciationPlugin, StatisticsFormat, StatisticsPlugin, TickLimitPlugin,
+};
+
+#[derive(Args)]
+pub struct RunArguments {
+    /// The master seed for every random decision. A random seed is chosen if this is left out.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// A TOML file to load the simulation configuration from.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    #[command(flatten)]
+    simulation: SimulationArguments,
+}
+
+/// The arguments shared by every subcommand which runs a simulation.
+#[derive(Args)]
+pub struct SimulationArguments {
+    /// Runs without a window or renderer, as fast as the CPU allows.
+    #[arg(long)]
+    headless: bool,
+    /// Stops the simulation after this many fixed timesteps.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    ticks: Option<u64>,
+    /// A directory to write the outputs of the run to, including a snapshot of the final world. It is created if it
+    /// does not exist.
+    #[arg(long, value_name = "DIRECTORY")]
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    pub(super) checkpoints: CheckpointArguments,
+    #[command(flatten)]
+    statistics: StatisticsArguments,
+    /// A file to log every birth and death to, in JSON Lines format. It is replaced by a new run, and appended to by a
+    /// resumed one.
+    #[arg(long, value_name = "PATH")]
+    lineage: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct StatisticsArguments {
+    /// A file to stream population statistics to. The format is chosen by its extension, either .csv or .jsonl. It is
+    /// replaced by a new run, and appended to by a resumed one, once any samples from after the checkpoint are removed.
+    #[arg(long, value_name = "PATH")]
+    statistics: Option<PathBuf>,
+    /// The time, in seconds of simulated time, between each statistics sample.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 1.0,
+        requires = "statistics"
+    )]
+    statistics_interval: f64,
+}
+
+#[derive(Args)]
+pub struct CheckpointArguments {
+    /// A directory to periodically save checkpoints to. It is created if it does not exist.
+    #[arg(long, value_name = "DIRECTORY")]
+    pub(super) checkpoint_dir: Option<PathBuf>,
+    /// Saves a checkpoint every time this many seconds of simulated time have passed.
+    #[arg(long, value_name = "SECONDS")]
+    checkpoint_interval: Option<f64>,
+    /// Saves a checkpoint every time this many creatures have been born.
+    #[arg(long, value_name = "BIRTHS", value_parser = clap::value_parser!(u32).range(1..))]
+    checkpoint_births: Option<u32>,
+    /// The number of checkpoints to keep. Older checkpoints are deleted.
+    #[arg(long, value_name = "COUNT", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    keep_checkpoints: u64,
+}
+
+impl CheckpointArguments {
+    /// Whether any checkpoint triggers were given.
+    pub(super) fn has_trigger(&self) -> bool {
+        self.checkpoint_interval.is_some() || self.checkpoint_births.is_some()
+    }
+}
+
+pub fn run(arguments: RunArguments) -> Result<()> {
+    let seed = arguments.seed.unwrap_or_else(rand::random);
+
+    let config = match &arguments.config {
+        Some(path) => SimulationConfig::load(path)?,
+        None => SimulationConfig::default(),
+    };
+
+    simulation_app(
+        &arguments.simulation,
+        config,
+        SimulationRng::from_seed(seed),
+    )?
+    .run();
+
+    Ok(())
+}
+
+/// Builds an app which runs the simulation, ready to be populated.
+pub fn simulation_app(
+    arguments: &SimulationArguments,
+    config: SimulationConfig,
+    rng: SimulationRng,
+) -> Result<App> {
+    let checkpoint_plugin = checkpoint_plugin(&arguments.checkpoints)?;
+    let statistics_plugin = statistics_plugin(&arguments.statistics)?;
+
+    if let Some(path) = &arguments.lineage {
+        // The log is only truncated once the simulation knows whether it was resumed.
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Could not open the lineage log {}.", path.display()))?;
+    }
+
+    if let Some(output) = &arguments.output {
+        write_run_description(output, rng.seed(), &config)?;
+    }
+
+    println!("Seed: {}", rng.seed());
+
+    let mut app = App::new();
+
+    // The configuration must be in place before any plugins are built.
+    app.insert_resource(config).insert_resource(rng);
+
+    if arguments.headless {
+        app.add_plugins(HeadlessPlugin)
+            .add_plugins(LogPlugin::default());
+    } else {
+        app.add_plugins(DefaultPlugins)
+            .add_plugins(SetupPlugin)
+            .add_plugins(AppearancePlugin);
+    }
+
+    app.add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(SpeciationPlugin);
+
+    if let Some(ticks) = arguments.ticks {
+        app.add_plugins(TickLimitPlugin { ticks });
+    }
+
+    if let Some(output) = &arguments.output {
+        app.add_plugins(SnapshotOnExitPlugin {
+            path: output.join("final.ron"),
+        });
+    }
+
+    if let Some(plugin) = checkpoint_plugin {
+        app.add_plugins(plugin);
+    }
+
+    if let Some(plugin) = statistics_plugin {
+        app.add_plugins(plugin);
+    }
+
+    if let Some(path) = &arguments.lineage {
+        app.add_plugins(LineagePlugin { path: path.clone() });
+    }
+
+    Ok(app)
+}
+
+fn checkpoint_plugin(arguments: &CheckpointArguments) -> Result<Option<CheckpointPlugin>> {
+    let Some(directory) = &arguments.checkpoint_dir else {
+        if arguments.has_trigger() {
+            bail!("--checkpoint-interval and --checkpoint-births require --checkpoint-dir.");
+        }
+
+        return Ok(None);
+    };
+
+    if !arguments.has_trigger() {
+        bail!("--checkpoint-dir requires --checkpoint-interval, --checkpoint-births or both.");
+    }
+
+    if let Some(interval) = arguments.checkpoint_interval
+        && !(interval.is_finite() && interval > 0.0)
+    {
+        bail!("--checkpoint-interval must be positive, but was {interval}.");
+    }
+
+    fs::create_dir_all(directory).with_context(|| {
+        format!(
+            "Could not create the checkpoint directory {}.",
+            directory.display()
+        )
+    })?;
+
+    Ok(Some(CheckpointPlugin {
+        directory: directory.clone(),
+        interval: arguments.checkpoint_interval,
+        births: arguments.checkpoint_births,
+        keep: arguments.keep_checkpoints as usize,
+    }))
+}
+
+fn statistics_plugin(arguments: &StatisticsArguments) -> Result<Option<StatisticsPlugin>> {
+    let Some(path) = &arguments.statistics else {
+        return Ok(None);
+    };
+
+    let Some(format) = StatisticsFormat::from_path(path) else {
+        bail!(
+            "The statistics file {} must end in either .csv or .jsonl.",
+            path.display()
+        );
+    };
+
+    let interval = arguments.statistics_interval;
+
+    if !(interval.is_finite() && interval > 0.0) {
+        bail!("--statistics-interval must be positive, but was {interval}.");
+    }
+
+    // Opening the file here means that a bad path is reported before the simulation starts. It is only truncated once
+    // the simulation knows whether it was resumed.
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Could not open the statistics file {}.", path.display()))?;
+
+    Ok(Some(StatisticsPlugin {
+        path: path.clone(),
+        format,
+        interval,
+    }))
+}
+
+/// Records the seed and configuration of a run, so that it can be reproduced later.
+fn write_run_description(output: &Path, seed: u64, config: &SimulationConfig) -> Result<()> {
+    fs::create_dir_all(output).with_context(|| {
+        format!(
+            "Could not create the output directory {}.",
+            output.display()
+        )
+    })?;
+
+    let config = toml::to_string(config).context("Could not serialise the configuration.")?;
+
+    fs::write(output.join("config.toml"), config).with_context(|| {
+        format!(
+            "Could not write to the output directory {}.",
+            output.display()
+        )
+    })?;
+
+    fs::write(output.join("seed"), format!("{seed}\n")).with_context(|| {
+        format!(
+            "Could not write to the output directory {}.",
+            output.display()
+        )
+    })?;
+
+    Ok(())
+}
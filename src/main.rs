@@ -1,13 +1,10 @@
-use bevy::prelude::*;
+mod cli;
 
-use evolut::simulation::{CreaturePlugin, FoodPlugin, SetupPlugin, SpatialIndexPlugin};
+use anyhow::Result;
+use clap::Parser;
 
-fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugins(SetupPlugin)
-        .add_plugins(CreaturePlugin)
-        .add_plugins(FoodPlugin)
-        .add_plugins(SpatialIndexPlugin)
-        .run();
+use cli::Cli;
+
+fn main() -> Result<()> {
+    Cli::parse().execute()
 }
@@ -0,0 +1,216 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    ActionOutput, ActivationFunction, Connection, InputNeuron, Neuron, SensoryInput, SensoryInputs,
+    SensoryNeuron,
+};
+
+/// A brain flattened into a list of neurons, ordered so that every neuron comes after all of its inputs.
+///
+/// Connections refer to their inputs by index, so a brain can be evaluated with a single pass over the neurons,
+/// writing each activation into a buffer which is allocated once, when the brain is compiled.
+///
+/// The buffer also serves as the memory of the brain. A recurrent connection leads back to a neuron which is at or
+/// after its own position in the list, so the activation it reads is the one left over from the previous evaluation.
+#[derive(Debug)]
+pub struct CompiledBrain {
+    neurons: Vec<CompiledNeuron>,
+    /// The inputs of every neuron, with those of each neuron next to each other.
+    connections: Vec<CompiledConnection>,
+    /// The index of each action neuron, and what it controls.
+    outputs: Vec<(usize, ActionOutput)>,
+    /// The activation of each neuron, as of the latest evaluation.
+    activations: Vec<f32>,
+}
+
+#[derive(Debug)]
+enum CompiledNeuron {
+    /// Reads one of the sensory inputs.
+    Sensory(SensoryInput),
+    /// Applies an activation function to the weighted sum of the activations of the connections in this range, plus a
+    /// bias.
+    Sum(Range<usize>, ActivationFunction, f32),
+}
+
+#[derive(Debug)]
+struct CompiledConnection {
+    /// The index of the input neuron.
+    source: usize,
+    weight: f32,
+}
+
+impl CompiledBrain {
+    /// Compiles the neurons of a brain, as built by [super::Brain::new].
+    ///
+    /// Only the neurons which lead to an action neuron are kept. A neuron which feeds into several others appears once.
+    pub fn compile(neurons: &[Neuron]) -> Self {
+        let mut compiler = Compiler::default();
+
+        for neuron in neurons {
+            if let Neuron::Action(action_neuron) = neuron {
+                let index = compiler.add_summing_neuron(
+                    action_neuron.inputs(),
+                    action_neuron.function(),
+                    action_neuron.bias(),
+                );
+                compiler.outputs.push((index, *action_neuron.output()));
+            }
+        }
+
+        // Recurrent connections lead back to neurons which are only compiled after them.
+        for (connection, id) in compiler.recurrent.drain(..) {
+            compiler.connections[connection].source = *compiler
+                .internal_indices
+                .get(&id)
+                .expect("A recurrent connection always leads back to a neuron in the same tree.");
+        }
+
+        Self {
+            activations: vec![0.0; compiler.neurons.len()],
+            neurons: compiler.neurons,
+            connections: compiler.connections,
+            outputs: compiler.outputs,
+        }
+    }
+
+    /// Computes the activation of every neuron, and returns the activation of each action neuron alongside what it
+    /// controls.
+    ///
+    /// For brains without recurrent connections, the results are identical, bit for bit, to evaluating the tree form
+    /// of the brain through [super::Activation].
+    pub fn evaluate(
+        &mut self,
+        sensory_inputs: &SensoryInputs,
+    ) -> impl Iterator<Item = (ActionOutput, f32)> + '_ {
+        for (index, neuron) in self.neurons.iter().enumerate() {
+            self.activations[index] = match neuron {
+                CompiledNeuron::Sensory(input) => sensory_inputs.value(input),
+                CompiledNeuron::Sum(inputs, function, bias) => function.apply(
+                    self.connections[inputs.clone()]
+                        .iter()
+                        .map(|connection| connection.weight * self.activations[connection.source])
+                        .sum::<f32>()
+                        + bias,
+                ),
+            };
+        }
+
+        self.outputs
+            .iter()
+            .map(|&(index, output)| (output, self.activations[index]))
+    }
+
+    /// Returns the activation of every neuron, as of the latest evaluation.
+    pub fn activations(&self) -> &[f32] {
+        &self.activations
+    }
+
+    /// Replaces the activation of every neuron, as returned by [CompiledBrain::activations].
+    ///
+    /// Returns false, and leaves the activations untouched, if there are not exactly as many as there are neurons.
+    pub fn restore_activations(&mut self, activations: &[f32]) -> bool {
+        if activations.len() != self.activations.len() {
+            return false;
+        }
+
+        self.activations.copy_from_slice(activations);
+
+        true
+    }
+
+    /// Returns the number of neurons, including sensory neurons.
+    pub fn neuron_count(&self) -> usize {
+        self.neurons.len()
+    }
+
+    /// Returns the number of connections.
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+}
+
+#[derive(Default)]
+struct Compiler {
+    neurons: Vec<CompiledNeuron>,
+    connections: Vec<CompiledConnection>,
+    outputs: Vec<(usize, ActionOutput)>,
+    /// The indices of the sensory neurons which have already been compiled, keyed by their address in the tree.
+    sensory_indices: HashMap<*const SensoryNeuron, usize>,
+    /// The indices of the internal neurons which have already been compiled, keyed by their id.
+    internal_indices: HashMap<u8, usize>,
+    /// The index of each recurrent connection, and the id of the neuron it leads back to.
+    recurrent: Vec<(usize, u8)>,
+}
+
+impl Compiler {
+    /// Compiles the inputs of a neuron, then the neuron itself, and returns its index.
+    ///
+    /// The tree is never deeper than the number of internal neurons, so recursion is safe here.
+    fn add_summing_neuron(
+        &mut self,
+        inputs: &[Connection],
+        function: ActivationFunction,
+        bias: f32,
+    ) -> usize {
+        // Recurrent connections have no source yet, and are filled in once every neuron has been compiled.
+        let sources: Vec<(Result<usize, u8>, f32)> = inputs
+            .iter()
+            .map(|connection| (self.add_input(connection.input()), connection.weight()))
+            .collect();
+
+        let start = self.connections.len();
+
+        for (source, weight) in sources {
+            let source = source.unwrap_or_else(|id| {
+                self.recurrent.push((self.connections.len(), id));
+                0
+            });
+
+            self.connections.push(CompiledConnection { source, weight });
+        }
+
+        self.neurons.push(CompiledNeuron::Sum(
+            start..self.connections.len(),
+            function,
+            bias,
+        ));
+
+        self.neurons.len() - 1
+    }
+
+    /// Compiles an input, unless it has been compiled already, and returns its index.
+    ///
+    /// Recurrent inputs are not compiled here, so the id of the neuron they lead back to is returned instead.
+    fn add_input(&mut self, input: &InputNeuron) -> Result<usize, u8> {
+        match input {
+            InputNeuron::Sensory(sensory_neuron) => {
+                let key = &**sensory_neuron as *const SensoryNeuron;
+
+                if let Some(&index) = self.sensory_indices.get(&key) {
+                    return Ok(index);
+                }
+
+                self.neurons
+                    .push(CompiledNeuron::Sensory(*sensory_neuron.input()));
+                self.sensory_indices.insert(key, self.neurons.len() - 1);
+
+                Ok(self.neurons.len() - 1)
+            }
+            InputNeuron::Internal(internal_neuron) => {
+                if let Some(&index) = self.internal_indices.get(&internal_neuron.id()) {
+                    return Ok(index);
+                }
+
+                let index = self.add_summing_neuron(
+                    internal_neuron.inputs(),
+                    internal_neuron.function(),
+                    internal_neuron.bias(),
+                );
+                self.internal_indices.insert(internal_neuron.id(), index);
+
+                Ok(index)
+            }
+            InputNeuron::Recurrent(id) => Err(*id),
+        }
+    }
+}
@@ -3,7 +3,6 @@ use std::{collections::HashMap, sync::Arc};
 use super::neuron::{Activation, InternalNeuron, SensoryInputs, SensoryNeuron};
 
 /// Represents a dependency on another neuron.
-// TODO: add biases as well as weights to a connection
 #[derive(Debug)]
 pub struct Connection {
     input: InputNeuron,
@@ -35,6 +34,11 @@ pub enum InputNeuron {
     Sensory(Arc<SensoryNeuron>),
     /// An internal neuron.
     Internal(Arc<InternalNeuron>),
+    /// The internal neuron with this id, as it was when the brain was last evaluated.
+    ///
+    /// This closes a cycle, so the neuron is one which the connection leads back to. The tree form of a brain has no
+    /// memory, so recurrent inputs always have an activation of zero there.
+    Recurrent(u8),
 }
 
 impl Activation for InputNeuron {
@@ -50,6 +54,7 @@ impl Activation for InputNeuron {
             InputNeuron::Internal(internal_neuron) => {
                 internal_neuron.activation(internal_activation_cache, sensory_inputs)
             }
+            InputNeuron::Recurrent(_) => 0.0,
         }
     }
 }
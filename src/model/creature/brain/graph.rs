@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::{collections::HashSet, fmt::Write};
+
+use super::{Brain, Connection, InputNeuron, Neuron};
+
+/// A brain as a graph of labelled neurons and the weighted connections between them, for studying it outside the
+/// simulation.
+#[derive(Debug, Serialize)]
+pub struct BrainGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A single neuron in a [BrainGraph].
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    /// A name which is unique within the graph, such as `Speed`, `Internal 3` or `Acceleration`.
+    pub id: String,
+    pub kind: NodeKind,
+    /// The activation function, which sensory neurons do not have.
+    pub function: Option<String>,
+    /// The bias which is added to the summed input, which sensory neurons do not have either.
+    pub bias: Option<f32>,
+}
+
+/// The types of neuron in a [BrainGraph].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Sensory,
+    Internal,
+    Action,
+}
+
+/// A connection in a [BrainGraph], from the neuron with the source id to the neuron with the destination id.
+#[derive(Debug, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub destination: String,
+    pub weight: f32,
+    /// Whether the connection reads the activation of its source from the previous evaluation.
+    pub recurrent: bool,
+}
+
+impl Brain {
+    /// Returns the brain as a graph. Each neuron appears once, however many others it feeds into.
+    pub fn graph(&self) -> BrainGraph {
+        let mut graph = BrainGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        let mut added_nodes: HashSet<String> = HashSet::new();
+
+        for neuron in self.neurons() {
+            if let Neuron::Action(action_neuron) = neuron {
+                let id = action_neuron.output().to_string();
+
+                graph.add_node(
+                    &mut added_nodes,
+                    id.clone(),
+                    NodeKind::Action,
+                    Some((action_neuron.function().to_string(), action_neuron.bias())),
+                );
+                graph.add_inputs(&mut added_nodes, &id, action_neuron.inputs());
+            }
+        }
+
+        graph
+    }
+}
+
+impl BrainGraph {
+    /// Adds a node, unless one with the same id has been added already, and returns whether it was added.
+    fn add_node(
+        &mut self,
+        added_nodes: &mut HashSet<String>,
+        id: String,
+        kind: NodeKind,
+        function: Option<(String, f32)>,
+    ) -> bool {
+        if !added_nodes.insert(id.clone()) {
+            return false;
+        }
+
+        let (function, bias) = function.unzip();
+
+        self.nodes.push(GraphNode {
+            id,
+            kind,
+            function,
+            bias,
+        });
+
+        true
+    }
+
+    /// Adds the inputs of a neuron, and an edge from each of them to it.
+    ///
+    /// The tree is never deeper than the number of internal neurons, so recursion is safe here.
+    fn add_inputs(
+        &mut self,
+        added_nodes: &mut HashSet<String>,
+        destination: &str,
+        inputs: &[Connection],
+    ) {
+        for connection in inputs {
+            let source = match connection.input() {
+                InputNeuron::Sensory(sensory_neuron) => {
+                    let id = sensory_neuron.input().to_string();
+
+                    self.add_node(added_nodes, id.clone(), NodeKind::Sensory, None);
+
+                    id
+                }
+                InputNeuron::Internal(internal_neuron) => {
+                    let id = internal_node_id(internal_neuron.id());
+
+                    // Each internal neuron's inputs are only added the first time it is found.
+                    if self.add_node(
+                        added_nodes,
+                        id.clone(),
+                        NodeKind::Internal,
+                        Some((
+                            internal_neuron.function().to_string(),
+                            internal_neuron.bias(),
+                        )),
+                    ) {
+                        self.add_inputs(added_nodes, &id, internal_neuron.inputs());
+                    }
+
+                    id
+                }
+                // A recurrent connection leads back to a neuron which is still being added, so it has a node already.
+                InputNeuron::Recurrent(id) => internal_node_id(*id),
+            };
+
+            self.edges.push(GraphEdge {
+                source,
+                destination: destination.to_string(),
+                weight: connection.weight(),
+                recurrent: matches!(connection.input(), InputNeuron::Recurrent(_)),
+            });
+        }
+    }
+
+    /// Writes the graph in the Graphviz DOT language.
+    ///
+    /// Sensory neurons are drawn as boxes on the left, and action neurons as double circles on the right. Other neurons
+    /// are labelled with their activation function and bias. Edges are labelled with their weight, coloured by its sign
+    /// and thickened by its size. Recurrent edges are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph brain {\n    rankdir=LR;\n");
+
+        for (kind, shape, rank) in [
+            (NodeKind::Sensory, "box", "source"),
+            (NodeKind::Internal, "ellipse", "same"),
+            (NodeKind::Action, "doublecircle", "sink"),
+        ] {
+            writeln!(dot, "    subgraph {{\n        rank={rank};").unwrap();
+
+            for node in self.nodes.iter().filter(|node| node.kind == kind) {
+                let label = match (&node.function, node.bias) {
+                    (Some(function), Some(bias)) => {
+                        format!("{}\\n{function} {bias:+.4}", node.id)
+                    }
+                    _ => node.id.clone(),
+                };
+
+                writeln!(
+                    dot,
+                    "        \"{}\" [label=\"{label}\", shape={shape}];",
+                    node.id
+                )
+                .unwrap();
+            }
+
+            dot.push_str("    }\n");
+        }
+
+        for edge in &self.edges {
+            let colour = if edge.weight.is_sign_negative() {
+                "firebrick"
+            } else {
+                "forestgreen"
+            };
+
+            // NaN weights are drawn as thinly as possible, rather than not at all.
+            let width = if edge.weight.is_nan() {
+                1.0
+            } else {
+                1.0 + edge.weight.abs().min(4.0)
+            };
+            let style = if edge.recurrent { "dashed" } else { "solid" };
+
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{:+.4}\", color={colour}, penwidth={width:.2}, style={style}];",
+                edge.source, edge.destination, edge.weight
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Writes the graph as pretty-printed JSON. JSON cannot represent NaN or infinite numbers, so they are written as
+    /// null.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("A brain graph can always be written as JSON.")
+    }
+}
+
+fn internal_node_id(id: u8) -> String {
+    format!("Internal {}", id - 128)
+}
@@ -1,59 +1,76 @@
 //! Houses all code related to a creature's brain.
 
+mod compiled;
 mod connection;
+mod graph;
 mod neuron;
+mod registry;
 
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use super::genome::{Gene, Genome};
-use crate::simulation::MAX_INTERNAL_NEURONS;
+pub use compiled::CompiledBrain;
 pub use connection::{Connection, InputNeuron};
+pub use graph::{BrainGraph, GraphEdge, GraphNode, NodeKind};
 pub use neuron::Activation;
 pub use neuron::{
-    ActionNeuron, ActionOutput, InternalNeuron, LinesOfSight, Neuron, SensoryInputs, SensoryNeuron,
+    ActionNeuron, ActionOutput, ActionOutputs, ActivationFunction, InternalNeuron, Neuron,
+    SensoryInput, SensoryInputs, SensoryNeuron,
 };
+pub use registry::NeuronRegistry;
+
+/// Controls how brains are built from genomes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrainConfig {
+    /// The maximum number of internal neurons a creature's brain can contain.
+    pub max_internal_neurons: u8,
+    /// What happens to genes which would form a cycle of internal neurons.
+    pub cycles: CycleHandling,
+}
+
+impl Default for BrainConfig {
+    fn default() -> Self {
+        Self {
+            max_internal_neurons: 10,
+            cycles: CycleHandling::Discard,
+        }
+    }
+}
+
+/// The ways in which genes that would form a cycle of internal neurons can be handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleHandling {
+    /// The genes are discarded, so brains are purely feedforward and have no memory.
+    Discard,
+    /// The genes become recurrent connections, which read the activation of their input from the previous
+    /// evaluation of the brain.
+    Recurrent,
+}
 
 /// A collection of neurons.
 ///
 /// The brain is a neural network, where the sensory neurons are the inputs to the network, and the action neurons
 /// are the outputs, directly modifying the behaviour of the creature.
-
+///
+/// The neurons are kept as a tree for introspection, but the brain is evaluated in its [CompiledBrain] form.
 #[derive(Component)]
 pub struct Brain {
     neurons: Vec<Neuron>,
+    compiled: CompiledBrain,
 }
 
 impl Brain {
-    /// Builds a new brain from a genome.
-    pub fn new(genome: &Genome) -> Self {
+    /// Builds a new brain from a genome, with the kinds of sensory input and action output in a registry.
+    pub fn new(genome: &Genome, config: &BrainConfig, registry: &NeuronRegistry) -> Self {
         // Build the working genome
         let mut working_genome: Vec<Option<Gene>> = genome
             .genes()
             .iter()
-            .map(|gene| {
-                // Calculating new source/destination ids is essential in order to know whether two neurons are the same.
-
-                let source_is_sensory_neuron = gene.source_id() < 128;
-
-                // Calculate the global source id
-                let source_id = if source_is_sensory_neuron {
-                    calculate_sensory_neuron_id(gene.source_id())
-                } else {
-                    calculate_internal_neuron_id(gene.source_id())
-                };
-
-                let destination_is_action_neuron = gene.destination_id() < 128;
-
-                // Calculate the global destination id
-                let destination_id = if destination_is_action_neuron {
-                    calculate_action_neuron_id(gene.destination_id())
-                } else {
-                    calculate_internal_neuron_id(gene.destination_id())
-                };
-
-                Some(Gene::new(source_id, destination_id, gene.weight()))
-            })
+            .map(|gene| Some(global_gene(gene, config, registry)))
             .collect();
 
         let mut working_neurons: Vec<(u8, Neuron)> = Vec::new();
@@ -81,6 +98,8 @@ impl Brain {
                     &mut working_genome,
                     &mut working_neurons,
                     &mut visited_neurons,
+                    config.cycles,
+                    registry,
                 );
 
                 if let Some(neuron) = action_neuron {
@@ -100,13 +119,49 @@ impl Brain {
             .map(|(_, neuron)| neuron)
             .collect();
 
-        Self { neurons }
+        let compiled = CompiledBrain::compile(&neurons);
+
+        Self { neurons, compiled }
     }
 
     /// Returns a reference to its neurons.
     pub fn neurons(&self) -> &Vec<Neuron> {
         &self.neurons
     }
+
+    /// Returns the compiled form of the brain.
+    pub fn compiled(&self) -> &CompiledBrain {
+        &self.compiled
+    }
+
+    /// Evaluates the brain, and returns the activation of each action neuron alongside what it controls.
+    pub fn evaluate(
+        &mut self,
+        sensory_inputs: &SensoryInputs,
+    ) -> impl Iterator<Item = (ActionOutput, f32)> + '_ {
+        self.compiled.evaluate(sensory_inputs)
+    }
+
+    /// Returns the activation of every neuron in the compiled brain, as of its latest evaluation.
+    ///
+    /// This is the memory which recurrent connections read from.
+    pub fn state(&self) -> &[f32] {
+        self.compiled.activations()
+    }
+
+    /// Replaces the activation of every neuron in the compiled brain, as returned by [Brain::state].
+    ///
+    /// Returns false, and leaves the brain untouched, if the state is for a brain of a different size.
+    pub fn restore_state(&mut self, state: &[f32]) -> bool {
+        self.compiled.restore_activations(state)
+    }
+
+    /// Returns the number of connections in the brain.
+    ///
+    /// An internal neuron which feeds into several others only has its own inputs counted once.
+    pub fn connection_count(&self) -> usize {
+        self.compiled.connection_count()
+    }
 }
 
 /* Dear my very confused future self.
@@ -155,19 +210,25 @@ fn build_tree(
     working_genome: &mut Vec<Option<Gene>>, // The list of genes which have not already been used/discarded
     working_neurons: &mut Vec<(u8, Neuron)>, // The list of neurons whose trees have been built
     visited_neurons: &mut Vec<u8>,          // The ids of the neurons who have already been visited
+    cycles: CycleHandling, // Whether genes which form cycles are discarded or made recurrent
+    registry: &NeuronRegistry, // The kinds of sensory input and action output which global ids refer to
 ) -> Option<Neuron> {
     // The list of connection inputs for the current neuron
     let mut inputs: Vec<Connection> = Vec::new();
 
+    // The activation function and bias of the current neuron, which are chosen by the first gene leading into it
+    let mut function: Option<ActivationFunction> = None;
+    let mut bias = 0.0;
+
     let mut gene_index = 0;
 
     while gene_index < working_genome.len() {
         // TODO: update this logic to use a while let pattern, then the un-wrappings can be removed.
 
         // Ignore any genes whose destination ids aren't the current neuron id
-        if !working_genome[gene_index]
+        if working_genome[gene_index]
             .as_ref()
-            .is_some_and(|g| g.destination_id() == neuron_id)
+            .is_none_or(|g| g.destination_id() != neuron_id)
         {
             gene_index += 1;
             continue;
@@ -177,6 +238,13 @@ fn build_tree(
         let source_id = working_genome[gene_index].as_ref().unwrap().source_id();
         let weight = working_genome[gene_index].as_ref().unwrap().weight();
 
+        if function.is_none() {
+            let gene = working_genome[gene_index].as_ref().unwrap();
+
+            function = Some(gene.activation_function());
+            bias = gene.bias();
+        }
+
         // If the most significant bit of the source id is 0 (i.e. the source id is less than 128), the source is a sensory neuron
         let source_is_sensory_neuron = source_id < 128;
         let source_is_internal_neuron = !source_is_sensory_neuron;
@@ -185,14 +253,9 @@ fn build_tree(
         working_genome[gene_index] = None;
 
         // See whether the source neuron has already been created (i.e. its tree has already been built)
-        let mut source_neuron_search = working_neurons.iter().filter(|(id, neuron)| {
-            *id == source_id
-                && if let Neuron::Action(_) = neuron {
-                    false
-                } else {
-                    true
-                }
-        });
+        let mut source_neuron_search = working_neurons
+            .iter()
+            .filter(|(id, neuron)| *id == source_id && !matches!(neuron, Neuron::Action(_)));
 
         if let Some((_, source_neuron)) = source_neuron_search.next() {
             // If the source neuron has already been created, create a new connection and add it to the list of inputs
@@ -212,7 +275,9 @@ fn build_tree(
             inputs.push(input);
         } else if source_neuron_search.next().is_none() && source_is_sensory_neuron {
             // If the source neuron hasn't yet been created, and the source is a sensory neuron, create it
-            let sensory_neuron = Arc::new(SensoryNeuron::new(source_id));
+            let sensory_neuron = Arc::new(SensoryNeuron::new(
+                registry.sensory_inputs()[source_id as usize],
+            ));
 
             // Add the sensory neuron to the list of neurons whose trees have been built
             working_neurons.push((source_id, Neuron::Sensory(Arc::clone(&sensory_neuron))));
@@ -224,7 +289,13 @@ fn build_tree(
         } else if source_neuron_search.next().is_none() && source_is_internal_neuron {
             if visited_neurons.contains(&source_id) {
                 // If the source neuron has already been visited while building an upstream tree, this means that the genome
-                // is coding for a loop or cycle. I am not allowing this, so we should discard this gene.
+                // is coding for a loop or cycle. The source is still being built, so it cannot be connected to directly.
+                // Either the gene is discarded, or it becomes a recurrent connection, which refers to the source by its id
+                // and reads its activation from the previous evaluation.
+                if cycles == CycleHandling::Recurrent {
+                    inputs.push(Connection::new(InputNeuron::Recurrent(source_id), weight));
+                }
+
                 continue;
             }
 
@@ -232,7 +303,14 @@ fn build_tree(
             visited_neurons.push(source_id);
 
             // Build the tree of the source neuron
-            let neuron = build_tree(source_id, working_genome, working_neurons, visited_neurons);
+            let neuron = build_tree(
+                source_id,
+                working_genome,
+                working_neurons,
+                visited_neurons,
+                cycles,
+                registry,
+            );
 
             if let Some(neuron) = neuron {
                 // If the source neuron was actually created (i.e. a valid tree could be built),
@@ -262,29 +340,60 @@ fn build_tree(
         gene_index += 1;
     }
 
-    if inputs.len() == 0 {
+    if inputs.is_empty() {
         return None;
     }
 
+    // A neuron with inputs always has at least one gene leading into it
+    let function = function.unwrap_or(ActivationFunction::Tanh);
+
     let neuron_is_action_neuron = neuron_id < 128;
 
     if neuron_is_action_neuron {
-        return Some(Neuron::Action(Arc::new(ActionNeuron::new(
-            neuron_id, inputs,
-        ))));
+        Some(Neuron::Action(Arc::new(ActionNeuron::new(
+            registry.action_outputs()[neuron_id as usize],
+            inputs,
+            function,
+            bias,
+        ))))
     } else {
-        return Some(Neuron::Internal(Arc::new(InternalNeuron::new(inputs))));
+        Some(Neuron::Internal(Arc::new(InternalNeuron::new(
+            neuron_id, inputs, function, bias,
+        ))))
     }
 }
 
-fn calculate_sensory_neuron_id(source_id: u8) -> u8 {
-    source_id % 9
-}
+/// Returns a copy of a gene with its source and destination ids replaced by global neuron ids.
+///
+/// Calculating new source/destination ids is essential in order to know whether two neurons are the same.
+pub(crate) fn global_gene(gene: &Gene, config: &BrainConfig, registry: &NeuronRegistry) -> Gene {
+    let source_is_sensory_neuron = gene.source_id() < 128;
+
+    // Calculate the global source id
+    let source_id = if source_is_sensory_neuron {
+        registry.sensory_neuron_id(gene.source_id())
+    } else {
+        calculate_internal_neuron_id(gene.source_id(), config.max_internal_neurons)
+    };
 
-fn calculate_action_neuron_id(destination_id: u8) -> u8 {
-    destination_id % 2
+    let destination_is_action_neuron = gene.destination_id() < 128;
+
+    // Calculate the global destination id
+    let destination_id = if destination_is_action_neuron {
+        registry.action_neuron_id(gene.destination_id())
+    } else {
+        calculate_internal_neuron_id(gene.destination_id(), config.max_internal_neurons)
+    };
+
+    Gene::new(
+        source_id,
+        destination_id,
+        gene.weight(),
+        gene.bias(),
+        gene.activation_id(),
+    )
 }
 
-fn calculate_internal_neuron_id(id: u8) -> u8 {
-    (id - 128) % MAX_INTERNAL_NEURONS + 128
+fn calculate_internal_neuron_id(id: u8, max_internal_neurons: u8) -> u8 {
+    (id - 128) % max_internal_neurons + 128
 }
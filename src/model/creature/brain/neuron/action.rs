@@ -1,8 +1,9 @@
-use std::{collections::HashMap, sync::Arc};
+use bevy::prelude::Component;
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use super::{
     super::{InputNeuron, connection::Connection},
-    Activation, InternalNeuron, SensoryInputs,
+    Activation, ActivationFunction, InternalNeuron, SensoryInputs,
 };
 
 /// The outputs of a creature's neural network.
@@ -10,18 +11,24 @@ use super::{
 pub struct ActionNeuron {
     inputs: Vec<Connection>,
     output: ActionOutput,
+    function: ActivationFunction,
+    bias: f32,
 }
 
 impl ActionNeuron {
     /// Creates a new action neuron.
-    pub fn new(action_neuron_id: u8, inputs: Vec<Connection>) -> Self {
-        let output = match action_neuron_id {
-            0 => ActionOutput::Acceleration,
-            1 => ActionOutput::AngularAcceleration,
-            _ => unreachable!(),
-        };
-
-        Self { inputs, output }
+    pub fn new(
+        output: ActionOutput,
+        inputs: Vec<Connection>,
+        function: ActivationFunction,
+        bias: f32,
+    ) -> Self {
+        Self {
+            inputs,
+            output,
+            function,
+            bias,
+        }
     }
 
     /// Returns a reference to its inputs.
@@ -33,6 +40,16 @@ impl ActionNeuron {
     pub fn output(&self) -> &ActionOutput {
         &self.output
     }
+
+    /// Returns the function which turns its summed input into its activation.
+    pub fn function(&self) -> ActivationFunction {
+        self.function
+    }
+
+    /// Returns the bias which is added to its summed input, once, however many inputs it has.
+    pub fn bias(&self) -> f32 {
+        self.bias
+    }
 }
 
 impl Activation for ActionNeuron {
@@ -42,7 +59,7 @@ impl Activation for ActionNeuron {
         internal_activation_cache: &mut HashMap<Arc<InternalNeuron>, f32>,
         sensory_inputs: &SensoryInputs,
     ) -> f32 {
-        let activation = self
+        let sum = self
             .inputs()
             .iter()
             .map(|connection| match connection.input() {
@@ -60,24 +77,72 @@ impl Activation for ActionNeuron {
                         connection.weight() * activation
                     }
                 }
-                InputNeuron::Sensory(sensory_neuron) => {
+                input => {
                     connection.weight()
-                        * sensory_neuron.activation(internal_activation_cache, sensory_inputs)
+                        * input.activation(internal_activation_cache, sensory_inputs)
                 }
             })
-            .sum::<f32>()
-            .tanh();
+            .sum::<f32>();
 
-        return activation;
+        self.function.apply(sum + self.bias)
     }
 }
 
-/// The output type of an action neuron.
-#[derive(Debug)]
-pub enum ActionOutput {
-    /// The acceleration to be applied to a creature's velocity.
-    Acceleration,
-    /// The angular acceleration to be applied to a creature's angular velocity.
-    AngularAcceleration,
-    // TODO: Add a neuron for reproduction.
+/// A kind of action output, as registered in a [super::super::NeuronRegistry].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActionOutput {
+    index: u8,
+    name: &'static str,
+}
+
+impl ActionOutput {
+    pub(in crate::model::creature::brain) fn new(index: u8, name: &'static str) -> Self {
+        Self { index, name }
+    }
+
+    /// Returns its position in the order in which action outputs were registered.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the name it was registered with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl Display for ActionOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The activation of each of a creature's action outputs as of its latest decision, in the order in which they were
+/// registered.
+///
+/// Outputs which the creature's brain has no action neuron for have no activation.
+#[derive(Component, Clone, Debug, Default)]
+pub struct ActionOutputs {
+    activations: Vec<Option<f32>>,
+}
+
+impl ActionOutputs {
+    /// Returns the activation of one of the outputs, if the brain has an action neuron for it.
+    pub fn activation(&self, output: &ActionOutput) -> Option<f32> {
+        self.activations.get(output.index()).copied().flatten()
+    }
+
+    /// Replaces the activation of one of the outputs.
+    pub fn set(&mut self, output: &ActionOutput, activation: f32) {
+        if self.activations.len() <= output.index() {
+            self.activations.resize(output.index() + 1, None);
+        }
+
+        self.activations[output.index()] = Some(activation);
+    }
+
+    /// Removes the activation of every output.
+    pub fn clear(&mut self) {
+        self.activations.fill(None);
+    }
 }
@@ -0,0 +1,73 @@
+use std::fmt::Display;
+
+/// The function which turns the summed input of a neuron into its activation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivationFunction {
+    /// Squashes the input to between -1 and 1.
+    Tanh,
+    /// Squashes the input to between 0 and 1.
+    Sigmoid,
+    /// Passes positive inputs through, and turns negative inputs into zero.
+    Relu,
+    /// Passes the input through unchanged.
+    Identity,
+    /// One for positive inputs, and zero otherwise.
+    Step,
+    /// The sine of the input, which lets a neuron respond to several ranges of input.
+    Sin,
+    /// One for an input of zero, falling away towards zero on either side.
+    Gaussian,
+}
+
+impl ActivationFunction {
+    /// Every activation function, in the order of their ids.
+    pub const ALL: [Self; 7] = [
+        Self::Tanh,
+        Self::Sigmoid,
+        Self::Relu,
+        Self::Identity,
+        Self::Step,
+        Self::Sin,
+        Self::Gaussian,
+    ];
+
+    /// Chooses an activation function from a gene's activation function id, modulo the number of functions.
+    pub fn from_id(id: u8) -> Self {
+        Self::ALL[id as usize % Self::ALL.len()]
+    }
+
+    /// Applies the function to the summed input of a neuron.
+    pub fn apply(self, input: f32) -> f32 {
+        match self {
+            Self::Tanh => input.tanh(),
+            Self::Sigmoid => 1.0 / (1.0 + (-input).exp()),
+            Self::Relu => input.max(0.0),
+            Self::Identity => input,
+            Self::Step => {
+                if input > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Sin => input.sin(),
+            Self::Gaussian => (-input * input).exp(),
+        }
+    }
+}
+
+impl Display for ActivationFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Tanh => "tanh",
+            Self::Sigmoid => "sigmoid",
+            Self::Relu => "ReLU",
+            Self::Identity => "identity",
+            Self::Step => "step",
+            Self::Sin => "sin",
+            Self::Gaussian => "Gaussian",
+        };
+
+        write!(f, "{name}")
+    }
+}
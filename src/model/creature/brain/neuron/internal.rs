@@ -6,25 +6,48 @@ use std::{
 
 use super::{
     super::connection::{Connection, InputNeuron},
-    Activation, SensoryInputs,
+    Activation, ActivationFunction, SensoryInputs,
 };
 
 /// Neurons which exist to facilitate more complexity in the neural network.
 #[derive(Debug)]
 pub struct InternalNeuron {
+    id: u8,
     inputs: Vec<Connection>,
+    function: ActivationFunction,
+    bias: f32,
 }
 
 impl InternalNeuron {
     /// Creates a new internal neuron.
-    pub fn new(inputs: Vec<Connection>) -> Self {
-        Self { inputs }
+    pub fn new(id: u8, inputs: Vec<Connection>, function: ActivationFunction, bias: f32) -> Self {
+        Self {
+            id,
+            inputs,
+            function,
+            bias,
+        }
+    }
+
+    /// Returns its global id, which is between 128 and 128 plus the maximum number of internal neurons.
+    pub fn id(&self) -> u8 {
+        self.id
     }
 
     /// Returns a reference to its inputs.
     pub fn inputs(&self) -> &Vec<Connection> {
         &self.inputs
     }
+
+    /// Returns the function which turns its summed input into its activation.
+    pub fn function(&self) -> ActivationFunction {
+        self.function
+    }
+
+    /// Returns the bias which is added to its summed input, once, however many inputs it has.
+    pub fn bias(&self) -> f32 {
+        self.bias
+    }
 }
 
 impl Activation for InternalNeuron {
@@ -34,7 +57,7 @@ impl Activation for InternalNeuron {
         internal_activation_cache: &mut HashMap<Arc<InternalNeuron>, f32>,
         sensory_inputs: &SensoryInputs,
     ) -> f32 {
-        let activation = self
+        let sum = self
             .inputs()
             .iter()
             .map(|connection| match connection.input() {
@@ -52,15 +75,14 @@ impl Activation for InternalNeuron {
                         connection.weight() * activation
                     }
                 }
-                InputNeuron::Sensory(sensory_neuron) => {
+                input => {
                     connection.weight()
-                        * sensory_neuron.activation(internal_activation_cache, sensory_inputs)
+                        * input.activation(internal_activation_cache, sensory_inputs)
                 }
             })
-            .sum::<f32>()
-            .tanh();
+            .sum::<f32>();
 
-        return activation;
+        self.function.apply(sum + self.bias)
     }
 }
 
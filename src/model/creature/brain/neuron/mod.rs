@@ -1,12 +1,14 @@
 mod action;
+mod function;
 mod internal;
 mod sensory;
 
 use std::{collections::HashMap, sync::Arc};
 
-pub use action::{ActionNeuron, ActionOutput};
+pub use action::{ActionNeuron, ActionOutput, ActionOutputs};
+pub use function::ActivationFunction;
 pub use internal::InternalNeuron;
-pub use sensory::{LinesOfSight, SensoryInputs, SensoryNeuron};
+pub use sensory::{SensoryInput, SensoryInputs, SensoryNeuron};
 
 /// Has a variant for each type of neuron.
 #[derive(Debug)]
@@ -1,4 +1,5 @@
-use std::{collections::HashMap, sync::Arc};
+use bevy::prelude::Component;
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use super::{Activation, InternalNeuron};
 
@@ -10,24 +11,7 @@ pub struct SensoryNeuron {
 
 impl SensoryNeuron {
     /// Creates a new sensory neuron.
-    pub fn new(sensory_neuron_id: u8) -> Self {
-        let input = match sensory_neuron_id {
-            0 => SensoryInput::Age,
-            1 => SensoryInput::Speed,
-            2 => SensoryInput::AngularVelocity,
-            3 => SensoryInput::StoredEnergy,
-            4..=9 => SensoryInput::LineOfSight(match sensory_neuron_id {
-                4 => LineOfSight::LeftCreature,
-                5 => LineOfSight::LeftFood,
-                6 => LineOfSight::MiddleCreature,
-                7 => LineOfSight::MiddleFood,
-                8 => LineOfSight::RightCreature,
-                9 => LineOfSight::RightFood,
-                _ => unreachable!(),
-            }),
-            _ => unreachable!(),
-        };
-
+    pub fn new(input: SensoryInput) -> Self {
         Self { input }
     }
 
@@ -43,57 +27,62 @@ impl Activation for SensoryNeuron {
         _internal_activation_cache: &mut HashMap<Arc<InternalNeuron>, f32>,
         sensory_inputs: &SensoryInputs,
     ) -> f32 {
-        // TODO: compute the actual sensory input values.
-        match self.input() {
-            SensoryInput::Age => sensory_inputs.age,
-            SensoryInput::AngularVelocity => sensory_inputs.angular_velocity,
-            SensoryInput::LineOfSight(line_of_sight) => match line_of_sight {
-                LineOfSight::LeftCreature => sensory_inputs.lines_of_sight.left_creature,
-                LineOfSight::LeftFood => sensory_inputs.lines_of_sight.left_food,
-                LineOfSight::MiddleCreature => sensory_inputs.lines_of_sight.middle_creature,
-                LineOfSight::MiddleFood => sensory_inputs.lines_of_sight.middle_food,
-                LineOfSight::RightCreature => sensory_inputs.lines_of_sight.right_creature,
-                LineOfSight::RightFood => sensory_inputs.lines_of_sight.right_food,
-            },
-            SensoryInput::Speed => sensory_inputs.speed,
-            SensoryInput::StoredEnergy => sensory_inputs.stored_energy,
-        }
+        sensory_inputs.value(self.input())
     }
 }
 
-#[derive(Debug)]
-pub enum SensoryInput {
-    Age,
-    Speed,
-    AngularVelocity,
-    LineOfSight(LineOfSight),
-    StoredEnergy,
+/// A kind of sensory input, as registered in a [super::super::NeuronRegistry].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SensoryInput {
+    index: u8,
+    name: &'static str,
 }
 
-#[derive(Debug)]
-pub enum LineOfSight {
-    LeftCreature,
-    LeftFood,
-    MiddleCreature,
-    MiddleFood,
-    RightCreature,
-    RightFood,
+impl SensoryInput {
+    pub(in crate::model::creature::brain) fn new(index: u8, name: &'static str) -> Self {
+        Self { index, name }
+    }
+
+    /// Returns its position in the order in which sensory inputs were registered.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the name it was registered with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
 }
 
+impl Display for SensoryInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The value of each of a creature's sensory inputs, in the order in which they were registered.
+#[derive(Component, Clone, Debug, Default)]
 pub struct SensoryInputs {
-    pub age: f32,
-    pub speed: f32,
-    pub angular_velocity: f32,
-    pub lines_of_sight: LinesOfSight,
-    pub stored_energy: f32,
+    values: Vec<f32>,
 }
 
-#[derive(Default)]
-pub struct LinesOfSight {
-    pub left_creature: f32,
-    pub left_food: f32,
-    pub middle_creature: f32,
-    pub middle_food: f32,
-    pub right_creature: f32,
-    pub right_food: f32,
+impl SensoryInputs {
+    /// Creates a new set of sensory inputs, with a value for each registered input.
+    pub fn new(values: Vec<f32>) -> Self {
+        Self { values }
+    }
+
+    /// Returns the value of one of the inputs, or zero if it has never been sensed.
+    pub fn value(&self, input: &SensoryInput) -> f32 {
+        self.values.get(input.index()).copied().unwrap_or(0.0)
+    }
+
+    /// Replaces the value of one of the inputs.
+    pub fn set(&mut self, input: &SensoryInput, value: f32) {
+        if self.values.len() <= input.index() {
+            self.values.resize(input.index() + 1, 0.0);
+        }
+
+        self.values[input.index()] = value;
+    }
 }
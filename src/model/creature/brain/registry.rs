@@ -0,0 +1,80 @@
+use bevy::prelude::Resource;
+
+use super::{ActionOutput, SensoryInput};
+
+/// The kinds of sensory input and action output which genes can connect, in the order in which they were registered.
+///
+/// The source id of a gene which starts at a sensory neuron chooses its input modulo the number of registered inputs,
+/// and the destination id of a gene which ends at an action neuron chooses its output modulo the number of registered
+/// outputs. Registering a new kind therefore changes the brain built from most genomes, so kinds must always be
+/// registered in the same order.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NeuronRegistry {
+    sensory_inputs: Vec<SensoryInput>,
+    action_outputs: Vec<ActionOutput>,
+}
+
+impl NeuronRegistry {
+    /// The most kinds of input, or of output, which gene ids below 128 can choose between.
+    pub const MAX_KINDS: usize = 128;
+
+    /// Registers a new kind of sensory input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [NeuronRegistry::MAX_KINDS] inputs have already been registered.
+    pub fn register_sensory_input(&mut self, name: &'static str) -> SensoryInput {
+        assert!(
+            self.sensory_inputs.len() < Self::MAX_KINDS,
+            "No more than {} sensory inputs can be registered.",
+            Self::MAX_KINDS
+        );
+
+        let input = SensoryInput::new(self.sensory_inputs.len() as u8, name);
+        self.sensory_inputs.push(input);
+
+        input
+    }
+
+    /// Registers a new kind of action output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [NeuronRegistry::MAX_KINDS] outputs have already been registered.
+    pub fn register_action_output(&mut self, name: &'static str) -> ActionOutput {
+        assert!(
+            self.action_outputs.len() < Self::MAX_KINDS,
+            "No more than {} action outputs can be registered.",
+            Self::MAX_KINDS
+        );
+
+        let output = ActionOutput::new(self.action_outputs.len() as u8, name);
+        self.action_outputs.push(output);
+
+        output
+    }
+
+    /// Returns every registered sensory input, in the order in which they were registered.
+    pub fn sensory_inputs(&self) -> &[SensoryInput] {
+        &self.sensory_inputs
+    }
+
+    /// Returns every registered action output, in the order in which they were registered.
+    pub fn action_outputs(&self) -> &[ActionOutput] {
+        &self.action_outputs
+    }
+
+    /// Returns the global id of the sensory neuron which a gene's source id chooses.
+    pub(crate) fn sensory_neuron_id(&self, source_id: u8) -> u8 {
+        source_id
+            .checked_rem(self.sensory_inputs.len() as u8)
+            .expect("At least one sensory input must be registered before brains are built.")
+    }
+
+    /// Returns the global id of the action neuron which a gene's destination id chooses.
+    pub(crate) fn action_neuron_id(&self, destination_id: u8) -> u8 {
+        destination_id
+            .checked_rem(self.action_outputs.len() as u8)
+            .expect("At least one action output must be registered before brains are built.")
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result, bail};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use super::{Gene, Genome};
+
+/// The version of the genome encodings. This must be increased whenever the encoding of a gene changes.
+///
+/// Genomes in version 1, which predates biases and activation functions, can still be read. Their genes are given a
+/// bias of zero and the tanh activation function, so they behave exactly as they did.
+pub const GENOME_CODEC_VERSION: u8 = 2;
+
+/// The number of bytes in each gene of a version 1 genome: the source and destination ids, then the weight.
+const LEGACY_GENE_BYTES: usize = 6;
+
+/// The start of every genome in text form, which is followed by the version.
+const TEXT_PREFIX: &str = "genome:v";
+
+/// The first bytes of every genome in binary form, which are followed by the version.
+const BINARY_MAGIC: &[u8] = b"EVG";
+
+impl Display for Genome {
+    /// Writes the genome as text: a versioned header, followed by the hex representation of each gene.
+    ///
+    /// For example, `genome:v2:00003f8000000000000000` is a genome with a single gene.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{TEXT_PREFIX}{GENOME_CODEC_VERSION}:")?;
+
+        for gene in self.genes() {
+            write!(f, "{}", gene.as_hex())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Genome {
+    type Err = anyhow::Error;
+
+    /// Reads a genome in the text form written by its [Display] implementation.
+    fn from_str(text: &str) -> Result<Self> {
+        let (version, genes) = text
+            .trim()
+            .strip_prefix(TEXT_PREFIX)
+            .and_then(|text| text.split_once(':'))
+            .ok_or(MissingGenomeHeader)?;
+
+        let version: u8 = version
+            .parse()
+            .with_context(|| format!("The genome version {version} is not a number."))?;
+
+        check_version(version)?;
+
+        if version == GENOME_CODEC_VERSION {
+            Genome::from_hex(genes)
+        } else {
+            genes_from_bytes(version, &hex_bytes(genes)?)
+        }
+    }
+}
+
+impl Genome {
+    /// Returns the genome in a compact binary form, encoded as URL-safe base64.
+    ///
+    /// The binary form is a magic number and a version, followed by each gene as returned by [Gene::to_bytes].
+    pub fn to_base64(&self) -> String {
+        let mut bytes =
+            Vec::with_capacity(BINARY_MAGIC.len() + 1 + self.genes().len() * Gene::BYTES);
+
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.push(GENOME_CODEC_VERSION);
+
+        for gene in self.genes() {
+            bytes.extend_from_slice(&gene.to_bytes());
+        }
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Reads a genome in the base64 form written by [Genome::to_base64].
+    pub fn from_base64(text: &str) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(text.trim())
+            .context("The genome is not valid base64.")?;
+
+        let (&version, genes) = bytes
+            .strip_prefix(BINARY_MAGIC)
+            .and_then(|bytes| bytes.split_first())
+            .ok_or(MissingGenomeHeader)?;
+
+        check_version(version)?;
+
+        genes_from_bytes(version, genes)
+    }
+}
+
+/// Reads the genes of a genome, in the binary form of the given version.
+fn genes_from_bytes(version: u8, genes: &[u8]) -> Result<Genome> {
+    let gene_bytes = if version == GENOME_CODEC_VERSION {
+        Gene::BYTES
+    } else {
+        LEGACY_GENE_BYTES
+    };
+
+    if !genes.len().is_multiple_of(gene_bytes) {
+        return Err(InvalidGenomeBinaryLength { gene_bytes }.into());
+    }
+
+    let genes = genes
+        .chunks_exact(gene_bytes)
+        .map(|gene| {
+            if version == GENOME_CODEC_VERSION {
+                Gene::from_bytes(gene.try_into().unwrap())
+            } else {
+                let weight = f32::from_be_bytes(gene[2..6].try_into().unwrap());
+
+                Gene::new(gene[0], gene[1], weight, 0.0, 0)
+            }
+        })
+        .collect();
+
+    Ok(Genome::new(genes))
+}
+
+/// Converts a hex string into the bytes it represents.
+fn hex_bytes(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(InvalidGenomeBinaryLength {
+            gene_bytes: LEGACY_GENE_BYTES,
+        }
+        .into());
+    }
+
+    hex.as_bytes()
+        .chunks(2)
+        .map(|byte| {
+            let byte = std::str::from_utf8(byte)?;
+
+            // A leading plus sign would otherwise be accepted in place of a digit.
+            if !byte.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+                bail!("{byte} is not a hexadecimal byte.");
+            }
+
+            u8::from_str_radix(byte, 16)
+                .with_context(|| format!("{byte} is not a hexadecimal byte."))
+        })
+        .collect()
+}
+
+fn check_version(version: u8) -> Result<()> {
+    if version == GENOME_CODEC_VERSION || version == 1 {
+        Ok(())
+    } else {
+        Err(UnsupportedGenomeVersion { version }.into())
+    }
+}
+
+/// An error returned when an encoded genome does not start with a header.
+#[derive(Debug)]
+struct MissingGenomeHeader;
+
+impl Display for MissingGenomeHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The genome does not start with a valid header.")
+    }
+}
+
+impl Error for MissingGenomeHeader {}
+
+/// An error returned when a genome was encoded in a version which cannot be read.
+#[derive(Debug)]
+struct UnsupportedGenomeVersion {
+    version: u8,
+}
+
+impl Display for UnsupportedGenomeVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The genome is version {}, but only versions 1 to {} are supported.",
+            self.version, GENOME_CODEC_VERSION
+        )
+    }
+}
+
+impl Error for UnsupportedGenomeVersion {}
+
+/// An error returned when a binary genome is not made up of whole genes.
+#[derive(Debug)]
+struct InvalidGenomeBinaryLength {
+    gene_bytes: usize,
+}
+
+impl Display for InvalidGenomeBinaryLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The length of the binary genome was not a multiple of {}.",
+            self.gene_bytes
+        )
+    }
+}
+
+impl Error for InvalidGenomeBinaryLength {}
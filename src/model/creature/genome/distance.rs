@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Gene, Genome};
+
+/// The largest difference which two weights, or two biases, can contribute, so that a single enormous, infinite or NaN
+/// value cannot outweigh the rest of the genome.
+const MAX_WEIGHT_DIFFERENCE: f32 = 8.0;
+
+/// Controls how much each kind of difference between two genomes counts towards the distance between them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DistanceConfig {
+    /// The cost of a source or destination ID, or an activation function, which differs.
+    pub id_coefficient: f32,
+    /// The cost of each unit of difference between two weights, or two biases.
+    pub weight_coefficient: f32,
+    /// The cost of a gene which has no counterpart, because the other genome is shorter.
+    pub length_coefficient: f32,
+}
+
+impl Default for DistanceConfig {
+    fn default() -> Self {
+        Self {
+            id_coefficient: 1.0,
+            weight_coefficient: 0.5,
+            length_coefficient: 1.0,
+        }
+    }
+}
+
+impl Genome {
+    /// Returns how different this genome is from another, as the mean cost of each position in the longer genome.
+    ///
+    /// Genes are lined up by their position, as they are in crossover. Each pair costs the configured amount for each
+    /// ID or activation function which differs, plus the differences between their weights and their biases, each of
+    /// which is capped at 8. Each gene beyond the end of the shorter genome costs the length coefficient. Identical
+    /// genomes, including two empty ones, are a distance of zero apart.
+    pub fn distance(&self, other: &Genome, config: &DistanceConfig) -> f32 {
+        let longest = self.genes.len().max(other.genes.len());
+
+        if longest == 0 {
+            return 0.0;
+        }
+
+        let shared: f32 = self
+            .genes
+            .iter()
+            .zip(&other.genes)
+            .map(|(gene, other_gene)| gene_distance(gene, other_gene, config))
+            .sum();
+
+        let unmatched = self.genes.len().abs_diff(other.genes.len()) as f32;
+
+        (shared + unmatched * config.length_coefficient) / longest as f32
+    }
+}
+
+fn gene_distance(gene: &Gene, other: &Gene, config: &DistanceConfig) -> f32 {
+    let mut distance = 0.0;
+
+    if gene.source_id() != other.source_id() {
+        distance += config.id_coefficient;
+    }
+
+    if gene.destination_id() != other.destination_id() {
+        distance += config.id_coefficient;
+    }
+
+    // Activation ids are compared by the function they choose, since several ids choose the same one.
+    if gene.activation_function() != other.activation_function() {
+        distance += config.id_coefficient;
+    }
+
+    let difference = value_difference(gene.weight(), other.weight())
+        + value_difference(gene.bias(), other.bias());
+
+    distance + difference * config.weight_coefficient
+}
+
+fn value_difference(value: f32, other: f32) -> f32 {
+    // Values are first compared bit for bit, so that a NaN value is no distance from a copy of itself. Otherwise, a NaN
+    // difference is treated as the largest possible one.
+    if value.to_bits() == other.to_bits() {
+        0.0
+    } else {
+        (value - other).abs().min(MAX_WEIGHT_DIFFERENCE)
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::{error::Error, fmt::Display};
 
+use crate::model::creature::brain::ActivationFunction;
+
 /// Represents one neural connection in a creature's brain.
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Gene {
     /// The source of the connection.
     /// If the most significant bit of the source id is a 0 (i.e. less than 128), the source is a sensory neuron.
@@ -12,9 +15,21 @@ pub struct Gene {
     destination_id: u8,
     /// The weight of the connection.
     weight: f32,
+    /// Chooses the bias of the destination neuron, if this is the first gene which leads to it. The bias is added to the
+    /// neuron's summed input once, so it does not grow with the number of inputs which the neuron has.
+    bias: f32,
+    /// Chooses the activation function of the destination neuron, if this is the first gene which leads to it.
+    /// Any value is valid, as it is taken modulo the number of activation functions.
+    activation_id: u8,
 }
 
 impl Gene {
+    /// The number of bytes in the binary representation of a gene.
+    pub const BYTES: usize = 11;
+
+    /// The number of characters in the hex representation of a gene.
+    pub const HEX_LENGTH: usize = Self::BYTES * 2;
+
     /// Returns the source id.
     pub fn source_id(&self) -> u8 {
         self.source_id
@@ -30,88 +45,136 @@ impl Gene {
         self.weight
     }
 
-    /// Returns a gene with a random source id, destination id and weight.
-    pub fn random() -> Self {
-        let mut generator = rand::thread_rng();
+    /// Returns the bias.
+    pub fn bias(&self) -> f32 {
+        self.bias
+    }
+
+    /// Returns the raw activation function id.
+    pub fn activation_id(&self) -> u8 {
+        self.activation_id
+    }
 
+    /// Returns the activation function which the gene chooses for its destination neuron.
+    pub fn activation_function(&self) -> ActivationFunction {
+        ActivationFunction::from_id(self.activation_id)
+    }
+
+    /// Returns a gene with a random source id, destination id, weight, bias and activation function.
+    pub fn random<R: Rng + ?Sized>(generator: &mut R) -> Self {
         Gene::new(
-            rand::random(),
-            rand::random(),
+            generator.r#gen(),
+            generator.r#gen(),
+            generator.gen_range(-1.0..=1.0),
             generator.gen_range(-1.0..=1.0),
+            generator.r#gen(),
         )
     }
 
     /// Creates a new gene.
-    pub fn new(source_id: u8, destination_id: u8, weight: f32) -> Self {
+    pub fn new(
+        source_id: u8,
+        destination_id: u8,
+        weight: f32,
+        bias: f32,
+        activation_id: u8,
+    ) -> Self {
         Self {
             source_id,
             destination_id,
             weight,
+            bias,
+            activation_id,
         }
     }
 
-    pub fn mutated(&self, mutation_rate: f32) -> Self {
-        let mutated_source_id = Self::mutate_u8(self.source_id, mutation_rate);
-        let mutated_destination_id = Self::mutate_u8(self.destination_id, mutation_rate);
-        let mutated_weight = Self::mutate_f32(self.weight, mutation_rate);
-
+    /// Returns a copy of the gene with a different weight.
+    pub fn with_weight(&self, weight: f32) -> Self {
         Self {
-            source_id: mutated_source_id,
-            destination_id: mutated_destination_id,
-            weight: mutated_weight,
-        }
-    }
-
-    fn mutate_u8(number: u8, mutation_rate: f32) -> u8 {
-        let mut generator = rand::thread_rng();
-        let mut mutated = number;
-
-        for i in 0..8 {
-            if generator.gen_range(0.0..=1.0) < mutation_rate {
-                mutated ^= 1 << i;
-            }
+            weight,
+            ..self.clone()
         }
-
-        return mutated;
     }
 
-    fn mutate_f32(number: f32, mutation_rate: f32) -> f32 {
-        let mut generator = rand::thread_rng();
-        let mut mutated = number.to_bits();
-
-        for i in 0..32 {
-            if generator.gen_range(0.0..=1.0) < mutation_rate {
-                mutated ^= 1 << i;
-            }
+    /// Returns a copy of the gene with a different bias.
+    pub fn with_bias(&self, bias: f32) -> Self {
+        Self {
+            bias,
+            ..self.clone()
         }
-
-        f32::from_bits(mutated)
     }
 
     /// Creates a new gene from a given hex string.
     pub fn from_hex(hex: &str) -> Result<Self> {
-        if hex.len() != 12 {
+        if hex.len() != Self::HEX_LENGTH {
             return Err(InvalidHexLength.into());
         }
 
-        let source_id = &hex[0..2];
-        let destination_id = &hex[2..4];
-        let weight = &hex[4..12];
+        // Parsing each byte on its own would accept a leading plus sign in place of a digit.
+        if !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(InvalidHexDigit.into());
+        }
 
-        let source_id = u8::from_str_radix(source_id, 16)?;
-        let destination_id = u8::from_str_radix(destination_id, 16)?;
-        let weight = f32::from_bits(u32::from_str_radix(weight, 16)?);
+        let mut bytes = [0; Self::BYTES];
 
-        Ok(Gene::new(source_id, destination_id, weight))
+        for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits)?, 16)?;
+        }
+
+        Ok(Gene::from_bytes(bytes))
     }
 
-    /// Returns the hex representation of a gene.
+    /// Returns the hex representation of a gene, which is its binary representation written out in hex.
     pub fn as_hex(&self) -> String {
-        format!(
-            "{:02x}{:02x}{:012x}",
-            self.source_id(),
-            self.destination_id(),
-            self.weight().to_bits(),
+        self.to_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Returns the binary representation of a gene: its source id, its destination id, the bits of its weight and
+    /// then of its bias, most significant byte first, and finally its activation function id.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let [w0, w1, w2, w3] = self.weight.to_bits().to_be_bytes();
+        let [b0, b1, b2, b3] = self.bias.to_bits().to_be_bytes();
+
+        [
+            self.source_id,
+            self.destination_id,
+            w0,
+            w1,
+            w2,
+            w3,
+            b0,
+            b1,
+            b2,
+            b3,
+            self.activation_id,
+        ]
+    }
+
+    /// Creates a new gene from its binary representation.
+    pub fn from_bytes(bytes: [u8; Self::BYTES]) -> Self {
+        let [
+            source_id,
+            destination_id,
+            w0,
+            w1,
+            w2,
+            w3,
+            b0,
+            b1,
+            b2,
+            b3,
+            activation_id,
+        ] = bytes;
+
+        Gene::new(
+            source_id,
+            destination_id,
+            f32::from_bits(u32::from_be_bytes([w0, w1, w2, w3])),
+            f32::from_bits(u32::from_be_bytes([b0, b1, b2, b3])),
+            activation_id,
         )
     }
 }
@@ -122,8 +185,27 @@ struct InvalidHexLength;
 
 impl Display for InvalidHexLength {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "The length of the provided hex string was not 12.")
+        write!(
+            f,
+            "The length of the provided hex string was not {}.",
+            Gene::HEX_LENGTH
+        )
     }
 }
 
 impl Error for InvalidHexLength {}
+
+/// An error returned when a hex string to be converted into a Gene contains something other than hex digits.
+#[derive(Debug)]
+struct InvalidHexDigit;
+
+impl Display for InvalidHexDigit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The provided hex string contained a character which is not a hex digit."
+        )
+    }
+}
+
+impl Error for InvalidHexDigit {}
@@ -1,13 +1,58 @@
 //! Houses all gene and genome-related code.
 
+mod codec;
+mod distance;
 mod gene;
+mod mutation;
+mod validation;
 
+use anyhow::{Context, Result};
 use bevy::prelude::Component;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display};
 
+pub use codec::GENOME_CODEC_VERSION;
+pub use distance::DistanceConfig;
 pub use gene::Gene;
+pub use mutation::{BitFlip, GaussianCreep, IdMutation, MutationConfig, MutationModel};
+pub use validation::{GeneProblem, InvalidGenome};
+
+/// Controls the mutations which change the structure of a genome, rather than the contents of its genes.
+///
+/// Each operator is applied at most once per child, with its own probability.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StructuralMutationConfig {
+    /// The probability that a random gene is inserted at a random position.
+    pub insertion_probability: f64,
+    /// The probability that a random gene is deleted.
+    pub deletion_probability: f64,
+    /// The probability that a random gene is copied, with the copy placed right after it.
+    pub duplication_probability: f64,
+    /// The probability that two random genes swap places.
+    pub swap_probability: f64,
+    /// Genomes are never shortened below this length.
+    pub min_length: usize,
+    /// Genomes are never lengthened beyond this length.
+    pub max_length: usize,
+}
+
+impl Default for StructuralMutationConfig {
+    fn default() -> Self {
+        Self {
+            insertion_probability: 0.01,
+            deletion_probability: 0.01,
+            duplication_probability: 0.01,
+            swap_probability: 0.01,
+            min_length: 1,
+            max_length: 100,
+        }
+    }
+}
 
 /// Represents a list of a creature's genes. This genome is required to build a creature's brain.
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct Genome {
     genes: Vec<Gene>,
 }
@@ -23,24 +68,160 @@ impl Genome {
         &self.genes
     }
 
-    pub fn random(length: usize) -> Self {
+    pub fn random<R: Rng + ?Sized>(length: usize, generator: &mut R) -> Self {
         let mut genes: Vec<Gene> = Vec::new();
 
         for _ in 0..length {
-            let gene = Gene::random();
+            let gene = Gene::random(generator);
             genes.push(gene);
         }
 
         Genome::new(genes)
     }
 
-    pub fn mutated(&self, mutation_rate: f32) -> Self {
+    /// Returns a copy of the genome, with each gene mutated by the given model.
+    pub fn mutated<M: MutationModel, R: Rng + ?Sized>(&self, model: &M, generator: &mut R) -> Self {
         let mut genes: Vec<Gene> = Vec::new();
 
         for gene in &self.genes {
-            genes.push(gene.mutated(mutation_rate));
+            genes.push(model.mutate(gene, generator));
         }
 
         Self { genes }
     }
+
+    /// Inserts, deletes, duplicates and swaps genes, each with the probability given in the config.
+    ///
+    /// Insertions and duplications are skipped if the genome is already at its maximum length, and deletions are
+    /// skipped if it is already at its minimum length.
+    pub fn mutated_structurally<R: Rng + ?Sized>(
+        &self,
+        config: &StructuralMutationConfig,
+        generator: &mut R,
+    ) -> Self {
+        let mut genes = self.genes.clone();
+
+        if generator.gen_bool(config.insertion_probability) && genes.len() < config.max_length {
+            let position = generator.gen_range(0..=genes.len());
+            genes.insert(position, Gene::random(generator));
+        }
+
+        if generator.gen_bool(config.deletion_probability)
+            && genes.len() > config.min_length
+            && !genes.is_empty()
+        {
+            let position = generator.gen_range(0..genes.len());
+            genes.remove(position);
+        }
+
+        if generator.gen_bool(config.duplication_probability)
+            && genes.len() < config.max_length
+            && !genes.is_empty()
+        {
+            let position = generator.gen_range(0..genes.len());
+            genes.insert(position + 1, genes[position].clone());
+        }
+
+        if generator.gen_bool(config.swap_probability) && genes.len() >= 2 {
+            let first = generator.gen_range(0..genes.len());
+            let second = generator.gen_range(0..genes.len());
+            genes.swap(first, second);
+        }
+
+        Self { genes }
+    }
+
+    /// Combines this genome with another, as the genome of a child which has both as parents.
+    ///
+    /// Genes are lined up by their position. Crossover points are chosen within the length of the shorter genome, so
+    /// any genes beyond its end come from whichever parent supplies the final segment: the other genome for single-point
+    /// crossover, and this genome otherwise.
+    pub fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Genome,
+        method: CrossoverMethod,
+        generator: &mut R,
+    ) -> Self {
+        let shared_length = self.genes.len().min(other.genes.len());
+
+        let genes = match method {
+            CrossoverMethod::SinglePoint => {
+                let point = generator.gen_range(0..=shared_length);
+
+                [&self.genes[..point], &other.genes[point..]].concat()
+            }
+            CrossoverMethod::TwoPoint => {
+                let first = generator.gen_range(0..=shared_length);
+                let second = generator.gen_range(0..=shared_length);
+                let (start, end) = (first.min(second), first.max(second));
+
+                [
+                    &self.genes[..start],
+                    &other.genes[start..end],
+                    &self.genes[end..],
+                ]
+                .concat()
+            }
+            CrossoverMethod::Uniform => self
+                .genes
+                .iter()
+                .enumerate()
+                .map(|(position, gene)| match other.genes.get(position) {
+                    Some(other_gene) if generator.r#gen() => other_gene.clone(),
+                    _ => gene.clone(),
+                })
+                .collect(),
+        };
+
+        Self { genes }
+    }
+
+    /// Creates a new genome from the hex representations of its genes, one after the other.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        if !hex.len().is_multiple_of(Gene::HEX_LENGTH) {
+            return Err(InvalidGenomeHexLength.into());
+        }
+
+        let genes = hex
+            .as_bytes()
+            .chunks(Gene::HEX_LENGTH)
+            .enumerate()
+            .map(|(position, gene)| {
+                std::str::from_utf8(gene)
+                    .map_err(anyhow::Error::from)
+                    .and_then(Gene::from_hex)
+                    .with_context(|| format!("The gene at position {position} is invalid."))
+            })
+            .collect::<Result<Vec<Gene>>>()?;
+
+        Ok(Genome::new(genes))
+    }
 }
+
+/// The ways in which the genomes of two parents can be combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossoverMethod {
+    /// The child takes the genes of one parent up to a random point, and the genes of the other after it.
+    SinglePoint,
+    /// The child takes the genes of one parent, except between two random points, where it takes the other's.
+    TwoPoint,
+    /// The child takes each gene from either parent, with equal probability.
+    Uniform,
+}
+
+/// An error returned when a hex string to be converted into a Genome is not made up of whole genes.
+#[derive(Debug)]
+struct InvalidGenomeHexLength;
+
+impl Display for InvalidGenomeHexLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The length of the provided hex string was not a multiple of {}.",
+            Gene::HEX_LENGTH
+        )
+    }
+}
+
+impl Error for InvalidGenomeHexLength {}
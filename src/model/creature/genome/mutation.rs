@@ -0,0 +1,161 @@
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use super::Gene;
+
+/// A way of mutating the contents of a gene, used whenever a genome is copied into a child.
+pub trait MutationModel {
+    /// Returns a mutated copy of a gene.
+    fn mutate<R: Rng + ?Sized>(&self, gene: &Gene, generator: &mut R) -> Gene;
+}
+
+/// Flips each bit of a gene with the same probability.
+///
+/// A single flip in the exponent of the weight or bias can make it enormous, infinite or NaN, so this is best used with
+/// a very low rate.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BitFlip {
+    /// On average, 1 in every 1 / rate bits will be flipped.
+    pub rate: f64,
+}
+
+impl Default for BitFlip {
+    fn default() -> Self {
+        Self { rate: 0.001 }
+    }
+}
+
+impl MutationModel for BitFlip {
+    fn mutate<R: Rng + ?Sized>(&self, gene: &Gene, generator: &mut R) -> Gene {
+        Gene::new(
+            flip_bits(gene.source_id() as u32, 8, self.rate, generator) as u8,
+            flip_bits(gene.destination_id() as u32, 8, self.rate, generator) as u8,
+            f32::from_bits(flip_bits(gene.weight().to_bits(), 32, self.rate, generator)),
+            f32::from_bits(flip_bits(gene.bias().to_bits(), 32, self.rate, generator)),
+            flip_bits(gene.activation_id() as u32, 8, self.rate, generator) as u8,
+        )
+    }
+}
+
+/// Nudges weights and biases by a normally distributed amount, keeping them within a fixed range.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GaussianCreep {
+    /// The probability that a gene's weight is perturbed. Its bias is perturbed with the same probability.
+    pub weight_probability: f64,
+    /// The standard deviation of each perturbation. This must be finite and not negative.
+    pub sigma: f32,
+    /// The lowest weight or bias a gene can have.
+    pub min_weight: f32,
+    /// The highest weight or bias a gene can have.
+    pub max_weight: f32,
+    /// How the source, destination and activation function IDs are mutated.
+    pub ids: IdMutation,
+    /// For bit flipping, the probability that each bit of an ID is flipped. For resampling, the probability that each
+    /// ID is replaced.
+    pub id_rate: f64,
+}
+
+impl Default for GaussianCreep {
+    fn default() -> Self {
+        Self {
+            weight_probability: 0.1,
+            sigma: 0.2,
+            min_weight: -4.0,
+            max_weight: 4.0,
+            ids: IdMutation::Resample,
+            id_rate: 0.01,
+        }
+    }
+}
+
+/// The ways in which a gene's source, destination and activation function IDs can be mutated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdMutation {
+    /// Each bit of an ID is flipped independently.
+    BitFlip,
+    /// An ID is replaced by an entirely random one.
+    Resample,
+}
+
+impl MutationModel for GaussianCreep {
+    fn mutate<R: Rng + ?Sized>(&self, gene: &Gene, generator: &mut R) -> Gene {
+        let mut mutate_id = |id: u8| match self.ids {
+            IdMutation::BitFlip => flip_bits(id as u32, 8, self.id_rate, generator) as u8,
+            IdMutation::Resample => {
+                if generator.gen_range(0.0..=1.0) < self.id_rate {
+                    generator.r#gen()
+                } else {
+                    id
+                }
+            }
+        };
+
+        let source_id = mutate_id(gene.source_id());
+        let destination_id = mutate_id(gene.destination_id());
+        let activation_id = mutate_id(gene.activation_id());
+
+        let weight = self.creep(gene.weight(), generator);
+        let bias = self.creep(gene.bias(), generator);
+
+        Gene::new(source_id, destination_id, weight, bias, activation_id)
+    }
+}
+
+impl GaussianCreep {
+    /// Perturbs a weight or bias with the configured probability, then clamps it into range.
+    fn creep<R: Rng + ?Sized>(&self, mut value: f32, generator: &mut R) -> f32 {
+        if generator.gen_range(0.0..=1.0) < self.weight_probability {
+            let perturbation =
+                Normal::new(0.0, self.sigma).expect("sigma must be finite and not negative.");
+
+            value += perturbation.sample(generator);
+        }
+
+        // Clamping also brings values from other models, or from old genomes, back into range.
+        if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(self.min_weight, self.max_weight)
+        }
+    }
+}
+
+/// Chooses which mutation model is used.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum MutationConfig {
+    BitFlip(BitFlip),
+    GaussianCreep(GaussianCreep),
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        Self::BitFlip(BitFlip::default())
+    }
+}
+
+impl MutationModel for MutationConfig {
+    fn mutate<R: Rng + ?Sized>(&self, gene: &Gene, generator: &mut R) -> Gene {
+        match self {
+            Self::BitFlip(model) => model.mutate(gene, generator),
+            Self::GaussianCreep(model) => model.mutate(gene, generator),
+        }
+    }
+}
+
+/// Flips each of the lowest `bits` bits of a number with the given probability.
+fn flip_bits<R: Rng + ?Sized>(number: u32, bits: u32, rate: f64, generator: &mut R) -> u32 {
+    let mut mutated = number;
+
+    for i in 0..bits {
+        if generator.gen_range(0.0..=1.0) < rate {
+            mutated ^= 1 << i;
+        }
+    }
+
+    mutated
+}
@@ -0,0 +1,237 @@
+use std::{collections::HashSet, error::Error, fmt::Display};
+
+use super::{Gene, Genome};
+use crate::model::creature::brain::{BrainConfig, CycleHandling, NeuronRegistry, global_gene};
+
+/// A problem with a single gene, found by [Genome::validate].
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeneProblem {
+    /// The weight is NaN or infinite, which poisons the activation of every neuron downstream of it.
+    NonFiniteWeight { index: usize, weight: f32 },
+    /// The bias is NaN or infinite, which poisons the activation of every neuron downstream of it, just like a
+    /// non-finite weight.
+    NonFiniteBias { index: usize, bias: f32 },
+    /// The gene connects an internal neuron to itself, so it is always discarded when the brain is built, unless cycles
+    /// are made recurrent.
+    SelfConnection { index: usize },
+    /// The gene leads to an internal neuron with no path to an action neuron, or comes from an internal neuron which
+    /// nothing leads into, so it can never affect behaviour. Internal neurons without inputs are left out of the brain,
+    /// along with every connection from them.
+    Unexpressed { index: usize },
+}
+
+impl GeneProblem {
+    /// Returns the position of the gene in its genome.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::NonFiniteWeight { index, .. }
+            | Self::NonFiniteBias { index, .. }
+            | Self::SelfConnection { index }
+            | Self::Unexpressed { index } => *index,
+        }
+    }
+
+    /// Whether the gene would damage the brain built from it, rather than just being ignored.
+    pub fn is_corrupt(&self) -> bool {
+        matches!(
+            self,
+            Self::NonFiniteWeight { .. } | Self::NonFiniteBias { .. }
+        )
+    }
+}
+
+impl Display for GeneProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonFiniteWeight { index, weight } => {
+                write!(f, "The gene at position {index} has a weight of {weight}.")
+            }
+            Self::NonFiniteBias { index, bias } => {
+                write!(f, "The gene at position {index} has a bias of {bias}.")
+            }
+            Self::SelfConnection { index } => write!(
+                f,
+                "The gene at position {index} connects an internal neuron to itself."
+            ),
+            Self::Unexpressed { index } => write!(
+                f,
+                "The gene at position {index} never reaches an action neuron."
+            ),
+        }
+    }
+}
+
+/// An error returned when one or more genes in a genome have problems.
+#[derive(Debug)]
+pub struct InvalidGenome {
+    problems: Vec<GeneProblem>,
+}
+
+impl InvalidGenome {
+    /// Returns every problem, in the order of the genes.
+    pub fn problems(&self) -> &Vec<GeneProblem> {
+        &self.problems
+    }
+
+    /// Whether any of the problems would damage the brain built from the genome.
+    pub fn is_corrupt(&self) -> bool {
+        self.problems.iter().any(GeneProblem::is_corrupt)
+    }
+}
+
+impl Display for InvalidGenome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The genome is invalid:")?;
+
+        for problem in &self.problems {
+            write!(f, "\n  - {problem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for InvalidGenome {}
+
+impl Genome {
+    /// Checks every gene for problems, given the configuration and registry which its brain would be built with.
+    pub fn validate(
+        &self,
+        config: &BrainConfig,
+        registry: &NeuronRegistry,
+    ) -> Result<(), InvalidGenome> {
+        let global_genes: Vec<Gene> = self
+            .genes()
+            .iter()
+            .map(|gene| global_gene(gene, config, registry))
+            .collect();
+
+        let is_internal = |id: u8| id >= 128;
+
+        // Find every internal neuron with a path to an action neuron, working backwards from the action neurons.
+        let mut expressed_neurons: HashSet<u8> = HashSet::new();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for gene in &global_genes {
+                let source = gene.source_id();
+                let destination = gene.destination_id();
+
+                let leads_to_action = !is_internal(destination)
+                    || (source != destination && expressed_neurons.contains(&destination));
+
+                if is_internal(source) && leads_to_action && expressed_neurons.insert(source) {
+                    changed = true;
+                }
+            }
+        }
+
+        // Find every internal neuron which can be built with at least one input, working forwards from the sensory
+        // neurons. When cycles are made recurrent, a gene which closes a cycle counts too, as it reads the previous
+        // activation of its source, whether or not anything else leads into it. In other cases, genes are only flagged
+        // if they can never be expressed, so cycles which are discarded still count.
+        let closes_cycle: Vec<bool> = global_genes
+            .iter()
+            .map(|gene| {
+                config.cycles == CycleHandling::Recurrent
+                    && is_internal(gene.source_id())
+                    && reaches(&global_genes, gene.destination_id(), gene.source_id())
+            })
+            .collect();
+
+        let mut driven_neurons: HashSet<u8> = HashSet::new();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (gene, &closes_cycle) in global_genes.iter().zip(&closes_cycle) {
+                let source = gene.source_id();
+                let destination = gene.destination_id();
+
+                let drives =
+                    !is_internal(source) || closes_cycle || driven_neurons.contains(&source);
+
+                if is_internal(destination) && drives && driven_neurons.insert(destination) {
+                    changed = true;
+                }
+            }
+        }
+
+        let mut problems = Vec::new();
+
+        for (index, gene) in global_genes.iter().enumerate() {
+            if !gene.weight().is_finite() {
+                problems.push(GeneProblem::NonFiniteWeight {
+                    index,
+                    weight: gene.weight(),
+                });
+            }
+
+            if !gene.bias().is_finite() {
+                problems.push(GeneProblem::NonFiniteBias {
+                    index,
+                    bias: gene.bias(),
+                });
+            }
+
+            let source = gene.source_id();
+            let destination = gene.destination_id();
+
+            if is_internal(destination)
+                && source == destination
+                && config.cycles == CycleHandling::Discard
+            {
+                problems.push(GeneProblem::SelfConnection { index });
+            } else if (is_internal(destination) && !expressed_neurons.contains(&destination))
+                || (is_internal(source) && !driven_neurons.contains(&source))
+            {
+                problems.push(GeneProblem::Unexpressed { index });
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidGenome { problems })
+        }
+    }
+
+    /// Returns a copy of the genome with its corrupt genes repaired, by setting any non-finite weights and biases to
+    /// zero.
+    pub fn repaired(&self) -> Self {
+        let repair = |value: f32| if value.is_finite() { value } else { 0.0 };
+
+        Self::new(
+            self.genes()
+                .iter()
+                .map(|gene| {
+                    gene.with_weight(repair(gene.weight()))
+                        .with_bias(repair(gene.bias()))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Whether there is a path of genes from one neuron to another, or they are the same neuron.
+fn reaches(genes: &[Gene], from: u8, to: u8) -> bool {
+    let mut reached = HashSet::from([from]);
+    let mut frontier = vec![from];
+
+    while let Some(neuron) = frontier.pop() {
+        if neuron == to {
+            return true;
+        }
+
+        for gene in genes {
+            if gene.source_id() == neuron && reached.insert(gene.destination_id()) {
+                frontier.push(gene.destination_id());
+            }
+        }
+    }
+
+    false
+}
@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use std::fmt::Display;
+
+use super::{
+    SimulationConfig,
+    creature::{Age, Energy},
+    food::{Food, FoodKind},
+    species::Species,
+};
+use crate::model::creature::{brain::Brain, genome::Genome};
+
+/// Gives creatures and food a visible body, and colours creatures by the current [ColourMode].
+///
+/// The simulation itself never spawns rendering components, so this plugin must only be added alongside a renderer
+/// (i.e. [DefaultPlugins]).
+pub struct AppearancePlugin;
+
+impl Plugin for AppearancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ColourMode>();
+
+        app.add_systems(Update, switch_colour_mode);
+        app.add_systems(
+            PostUpdate,
+            (
+                (give_creatures_bodies, colour_creatures).chain(),
+                give_food_bodies,
+            ),
+        );
+    }
+}
+
+/// What the colour of each creature shows. Pressing C switches to the next mode.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColourMode {
+    /// Creatures with similar genomes have similar colours, so that lineages stand out.
+    #[default]
+    Genome,
+    /// From pale red for starving creatures, through magenta, to blue for those with enough energy to reproduce.
+    Energy,
+    /// From pale to dark brown, with the oldest creature alive darkest.
+    Age,
+    /// Each species has its own hue. Creatures which have not been placed in a species yet are grey.
+    Species,
+    /// From pale to dark purple, with the largest brain alive darkest.
+    BrainSize,
+}
+
+impl ColourMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Genome => Self::Energy,
+            Self::Energy => Self::Age,
+            Self::Age => Self::Species,
+            Self::Species => Self::BrainSize,
+            Self::BrainSize => Self::Genome,
+        }
+    }
+}
+
+impl Display for ColourMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Genome => "genome",
+            Self::Energy => "energy",
+            Self::Age => "age",
+            Self::Species => "species",
+            Self::BrainSize => "brain size",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+fn switch_colour_mode(keyboard_input: Res<ButtonInput<KeyCode>>, mut mode: ResMut<ColourMode>) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        *mode = mode.next();
+
+        info!("Colouring creatures by {}.", *mode);
+    }
+}
+
+fn give_creatures_bodies(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query: Query<Entity, Added<Brain>>,
+    config: Res<SimulationConfig>,
+) {
+    for entity in &query {
+        // Bodies are drawn at the size which the physics gives them, so creatures are seen to touch when they collide.
+        let body = meshes.add(Circle::new(config.physics.radius));
+
+        // Every creature has a material of its own, which is coloured in by colour_creatures.
+        commands.entity(entity).insert((
+            Mesh2d(body),
+            MeshMaterial2d(materials.add(ColorMaterial::default())),
+            Visibility::Visible,
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn colour_creatures(
+    query: Query<(
+        &MeshMaterial2d<ColorMaterial>,
+        &Genome,
+        &Brain,
+        &Energy,
+        &Age,
+        Option<&Species>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mode: Res<ColourMode>,
+    config: Res<SimulationConfig>,
+) {
+    // Age and brain size have no natural maximum, so they are scaled to the population.
+    let oldest = query
+        .iter()
+        .map(|(.., age, _)| age.value)
+        .fold(0.0, f32::max);
+    let largest_brain = query
+        .iter()
+        .map(|(_, _, brain, ..)| brain.connection_count())
+        .max()
+        .unwrap_or_default();
+
+    for (material, genome, brain, energy, age, species) in &query {
+        let colour = match *mode {
+            ColourMode::Genome => genome_colour(genome),
+            ColourMode::Energy => {
+                let fraction = energy.value / config.energy.reproduction_threshold;
+
+                Color::hsl(360.0 - 120.0 * fraction.clamp(0.0, 1.0), 0.8, 0.5)
+            }
+            ColourMode::Age => Color::hsl(30.0, 0.6, 0.8 - 0.6 * fraction(age.value, oldest)),
+            ColourMode::Species => match species {
+                // Successive species are a golden angle apart, so that their hues are spread out.
+                Some(species) => Color::hsl((species.id.0 as f32 * 137.508) % 360.0, 0.7, 0.5),
+                None => Color::srgb(0.6, 0.6, 0.6),
+            },
+            ColourMode::BrainSize => {
+                let size = fraction(brain.connection_count() as f32, largest_brain as f32);
+
+                Color::hsl(270.0, 0.6, 0.8 - 0.6 * size)
+            }
+        };
+
+        // Borrowing a material mutably marks it as modified, and so uploads it again, even if nothing is changed.
+        let unchanged = materials
+            .get(&material.0)
+            .is_none_or(|material| material.color == colour);
+
+        if !unchanged && let Some(material) = materials.get_mut(&material.0) {
+            material.color = colour;
+        }
+    }
+}
+
+/// Returns how far a value is towards a maximum, or zero if the maximum is zero.
+fn fraction(value: f32, maximum: f32) -> f32 {
+    if maximum > 0.0 {
+        (value / maximum).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Projects a genome into colour space, so that genomes which share most of their genes have similar colours.
+///
+/// Each gene is given a direction in RGB space which depends on its source and destination IDs, and is scaled by its
+/// weight. The directions are summed, and the sum is squashed into the range of each channel. Changing a single gene
+/// only moves the colour a little, while unrelated genomes are likely to be far apart.
+fn genome_colour(genome: &Genome) -> Color {
+    let mut sum = Vec3::ZERO;
+
+    for gene in genome.genes() {
+        let source = direction(gene.source_id() as u32);
+        let destination = direction(0x100 | gene.destination_id() as u32);
+        let connection =
+            direction(0x10000 | (gene.source_id() as u32) << 8 | gene.destination_id() as u32);
+
+        // NaN weights would otherwise turn the whole colour into NaN.
+        let weight = if gene.weight().is_nan() {
+            0.0
+        } else {
+            gene.weight().tanh()
+        };
+
+        sum += source + destination + connection * weight;
+    }
+
+    // Dividing by the square root of the length, rather than the length, keeps long genomes from all looking grey.
+    let sum = sum / (genome.genes().len().max(1) as f32).sqrt();
+
+    Color::srgb(
+        0.5 + 0.5 * sum.x.tanh(),
+        0.5 + 0.5 * sum.y.tanh(),
+        0.5 + 0.5 * sum.z.tanh(),
+    )
+}
+
+/// Returns a fixed, pseudorandom vector with each component between -1 and 1.
+fn direction(seed: u32) -> Vec3 {
+    // The SplitMix64 finaliser, which spreads nearby seeds far apart.
+    let mut hash = (seed as u64).wrapping_add(0x9e3779b97f4a7c15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^= hash >> 31;
+
+    let component = |shift: u32| ((hash >> shift) & 0xffff) as f32 / 0xffff as f32 * 2.0 - 1.0;
+
+    Vec3::new(component(0), component(16), component(32))
+}
+
+fn give_food_bodies(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query: Query<(Entity, &Food), Added<Food>>,
+) {
+    for (entity, food) in &query {
+        let circle = meshes.add(Circle::new(0.5));
+
+        let colour = match food.kind {
+            FoodKind::Plant => Color::linear_rgb(0.0, 1.0, 0.0),
+            FoodKind::Fruit => Color::linear_rgb(1.0, 0.5, 0.0),
+            FoodKind::Carrion => Color::linear_rgb(0.4, 0.2, 0.1),
+            FoodKind::Toxic => Color::linear_rgb(0.6, 0.0, 1.0),
+        };
+
+        commands.entity(entity).insert((
+            Mesh2d(circle),
+            MeshMaterial2d(materials.add(colour)),
+            Visibility::Visible,
+        ));
+    }
+}
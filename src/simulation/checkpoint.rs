@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use std::{
+    error::Error,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::{creature::CreatureBorn, period_elapsed, snapshot::Snapshot};
+
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+const CHECKPOINT_EXTENSION: &str = ".ron";
+
+/// Periodically saves a snapshot of the world to a directory, so that a run which is interrupted can be resumed.
+///
+/// A checkpoint is written whenever `interval` seconds of simulated time have passed, or `births` creatures have been
+/// born since the previous checkpoint, whichever comes first. Only the most recent `keep` checkpoints are kept.
+pub struct CheckpointPlugin {
+    pub directory: PathBuf,
+    pub interval: Option<f64>,
+    pub births: Option<u32>,
+    pub keep: usize,
+}
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Checkpoints {
+            directory: self.directory.clone(),
+            interval: self.interval.map(Duration::from_secs_f64),
+            births: self.births,
+            keep: self.keep.max(1),
+            births_since_last: 0,
+        });
+
+        app.add_systems(
+            FixedLast,
+            (count_births, write_checkpoint.run_if(checkpoint_due)).chain(),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct Checkpoints {
+    directory: PathBuf,
+    interval: Option<Duration>,
+    births: Option<u32>,
+    keep: usize,
+    births_since_last: u32,
+}
+
+fn count_births(mut births: EventReader<CreatureBorn>, mut checkpoints: ResMut<Checkpoints>) {
+    checkpoints.births_since_last += births.read().count() as u32;
+}
+
+fn checkpoint_due(checkpoints: Res<Checkpoints>, time: Res<Time<Fixed>>) -> bool {
+    let interval_elapsed = checkpoints
+        .interval
+        .is_some_and(|interval| period_elapsed(&time, interval));
+
+    let enough_births = checkpoints
+        .births
+        .is_some_and(|births| checkpoints.births_since_last >= births);
+
+    interval_elapsed || enough_births
+}
+
+fn write_checkpoint(world: &mut World) {
+    world.resource_mut::<Checkpoints>().births_since_last = 0;
+
+    let timestep = world.resource::<Time<Fixed>>().timestep().as_nanos().max(1);
+    let tick = world.resource::<Time<Fixed>>().elapsed().as_nanos() / timestep;
+
+    let checkpoints = world.resource::<Checkpoints>();
+    let directory = checkpoints.directory.clone();
+    let keep = checkpoints.keep;
+
+    let path = directory.join(format!(
+        "{CHECKPOINT_PREFIX}{tick:012}{CHECKPOINT_EXTENSION}"
+    ));
+
+    let result = Snapshot::capture(world)
+        .save(&path)
+        .and_then(|()| remove_old_checkpoints(&directory, keep));
+
+    if let Err(error) = result {
+        error!("{error:?}");
+    }
+}
+
+/// Lists the checkpoints in a directory, from oldest to newest.
+fn checkpoint_paths(directory: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(directory).with_context(|| {
+        format!(
+            "Could not read the checkpoint directory {}.",
+            directory.display()
+        )
+    })?;
+
+    let mut paths = Vec::new();
+
+    for entry in entries {
+        let path = entry
+            .with_context(|| {
+                format!(
+                    "Could not read the checkpoint directory {}.",
+                    directory.display()
+                )
+            })?
+            .path();
+
+        let is_checkpoint = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with(CHECKPOINT_PREFIX) && name.ends_with(CHECKPOINT_EXTENSION)
+            });
+
+        if is_checkpoint {
+            paths.push(path);
+        }
+    }
+
+    // The tick in each name is zero padded, so sorting by name also sorts by age.
+    paths.sort();
+
+    Ok(paths)
+}
+
+fn remove_old_checkpoints(directory: &Path, keep: usize) -> Result<()> {
+    let paths = checkpoint_paths(directory)?;
+
+    for path in &paths[..paths.len().saturating_sub(keep)] {
+        fs::remove_file(path)
+            .with_context(|| format!("Could not remove the checkpoint {}.", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// The most recent checkpoint in a directory which could be read.
+pub struct LatestCheckpoint {
+    pub path: PathBuf,
+    pub snapshot: Snapshot,
+    /// The newer checkpoints which could not be read, newest first.
+    pub skipped: Vec<SkippedCheckpoint>,
+}
+
+/// A checkpoint which was passed over, and why.
+#[derive(Debug)]
+pub struct SkippedCheckpoint {
+    pub path: PathBuf,
+    pub error: anyhow::Error,
+}
+
+impl Display for SkippedCheckpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Skipping {}: {:#}", self.path.display(), self.error)
+    }
+}
+
+/// Loads the most recent checkpoint in a directory which can be read successfully.
+///
+/// Checkpoints which are corrupt, or were written by an incompatible version, are skipped. This usually runs before
+/// any logging is set up, so rather than being logged, the skipped checkpoints are returned for the caller to report.
+pub fn latest_checkpoint(directory: &Path) -> Result<LatestCheckpoint> {
+    let mut skipped = Vec::new();
+
+    for path in checkpoint_paths(directory)?.into_iter().rev() {
+        match Snapshot::load(&path) {
+            Ok(snapshot) => {
+                return Ok(LatestCheckpoint {
+                    path,
+                    snapshot,
+                    skipped,
+                });
+            }
+            Err(error) => skipped.push(SkippedCheckpoint { path, error }),
+        }
+    }
+
+    Err(NoValidCheckpoint {
+        directory: directory.to_path_buf(),
+        skipped,
+    }
+    .into())
+}
+
+/// An error returned when a checkpoint directory contains no checkpoints which can be resumed from.
+#[derive(Debug)]
+pub struct NoValidCheckpoint {
+    directory: PathBuf,
+    /// Every checkpoint in the directory, newest first, with the reason it could not be read.
+    skipped: Vec<SkippedCheckpoint>,
+}
+
+impl NoValidCheckpoint {
+    /// Returns every checkpoint which was found but could not be read, newest first.
+    pub fn skipped(&self) -> &[SkippedCheckpoint] {
+        &self.skipped
+    }
+}
+
+impl Display for NoValidCheckpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "There are no valid checkpoints in {}.",
+            self.directory.display()
+        )?;
+
+        for skipped in &self.skipped {
+            write!(f, "\n{skipped}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for NoValidCheckpoint {}
@@ -0,0 +1,848 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display, fs, path::Path};
+
+use super::FoodKind;
+use crate::model::creature::{
+    brain::BrainConfig,
+    genome::{CrossoverMethod, DistanceConfig, MutationConfig, StructuralMutationConfig},
+};
+
+/// Every tuning knob of the simulation.
+///
+/// The configuration must be inserted into the app before any of the simulation plugins are added, as some of them
+/// read it while being built. Any plugin which finds no configuration will fall back to the defaults.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    pub world: WorldConfig,
+    pub time: TimeConfig,
+    pub physics: PhysicsConfig,
+    pub creatures: CreatureConfig,
+    pub energy: EnergyConfig,
+    pub food: FoodConfig,
+    pub reproduction: ReproductionConfig,
+    pub mutation: MutationConfig,
+    pub structural_mutation: StructuralMutationConfig,
+    pub brain: BrainConfig,
+    pub speciation: SpeciationConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldConfig {
+    /// The bounds of the world.
+    pub bounds: f32,
+    /// The distance that a creature is able to see.
+    pub seeing_distance: f32,
+    /// What happens at the bounds of the world.
+    pub topology: Topology,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            bounds: 1000.0,
+            seeing_distance: 10.0,
+            topology: Topology::default(),
+        }
+    }
+}
+
+/// The shape of the world, which decides what happens to creatures which reach its bounds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Topology {
+    /// The world is surrounded by walls, which creatures cannot pass through.
+    Walls(Walls),
+    /// Leaving the world on one side enters it on the opposite side, and creatures can see across the seam.
+    Toroidal,
+    /// The world is open, but creatures beyond its bounds are pushed back towards them.
+    Repulsive(RepulsiveBorder),
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self::Walls(Walls::default())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Walls {
+    /// What happens to the velocity of a creature which hits a wall.
+    pub collision: WallCollision,
+}
+
+/// The ways in which a creature can hit a wall.
+///
+/// This is written as a string, as RON writes other unit variants in a way which it cannot read back from inside an
+/// internally tagged enum such as [Topology].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", into = "&'static str")]
+pub enum WallCollision {
+    /// The creature bounces off, with its velocity towards the wall reversed.
+    #[default]
+    Reflect,
+    /// The creature stops, with its velocity towards the wall removed.
+    Stop,
+}
+
+impl From<WallCollision> for &'static str {
+    fn from(collision: WallCollision) -> Self {
+        match collision {
+            WallCollision::Reflect => "reflect",
+            WallCollision::Stop => "stop",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepulsiveBorder {
+    /// The acceleration towards the bounds of a creature beyond them, for each unit of distance beyond them.
+    pub strength: f32,
+}
+
+impl Default for RepulsiveBorder {
+    fn default() -> Self {
+        Self { strength: 1.0 }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeConfig {
+    /// The frequency, measured in Hz, at which the physics system should be updated.
+    pub fixed_update_frequency: f64,
+    /// The frequency, measured in Hz, at which the creatures should recalculate their brain state.
+    pub brain_update_frequency: f64,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            fixed_update_frequency: 1000.0,
+            brain_update_frequency: 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhysicsConfig {
+    /// The mass of every creature. The same force accelerates a heavier creature, and turns it, more slowly.
+    pub mass: f32,
+    /// The force with which a creature pushes itself forwards when its acceleration action has an activation of 1.
+    pub thrust: f32,
+    /// The torque with which a creature turns itself when its angular acceleration action has an activation of 1.
+    pub torque: f32,
+    /// The proportion of its velocity, per second, which a creature loses to drag.
+    pub linear_drag: f32,
+    /// The proportion of its angular velocity, per second, which a creature loses to drag.
+    pub angular_drag: f32,
+    /// The fastest that a creature can move.
+    pub max_speed: f32,
+    /// The fastest that a creature can turn, in radians per second.
+    pub max_angular_speed: f32,
+    /// The radius of every creature's body. Creatures whose bodies overlap collide. This can be at most the seeing
+    /// distance.
+    pub radius: f32,
+    /// The proportion of their speed towards each other which two colliding creatures keep as they bounce apart, from 0
+    /// for no bounce to 1 for a perfectly elastic collision.
+    pub restitution: f32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            thrust: 10.0,
+            torque: 10.0,
+            linear_drag: 1.0,
+            angular_drag: 1.0,
+            max_speed: 20.0,
+            max_angular_speed: 10.0,
+            radius: 1.0,
+            restitution: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CreatureConfig {
+    /// The number of creatures in the first generation.
+    pub generation_zero_size: u32,
+    /// The number of genes in the genomes of the first generation.
+    pub genome_length: usize,
+    /// The initial energy a creature should have.
+    pub initial_energy: f32,
+    /// What happens to corrupt genomes before brains are built from them.
+    pub genome_sanitisation: GenomeSanitisation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenomeSanitisation {
+    /// Genomes are used as they are.
+    Off,
+    /// Corrupt genes are repaired, by setting any non-finite weights and biases to zero.
+    Repair,
+    /// Creatures with corrupt genomes are never spawned. Parents still pay the cost of reproduction.
+    Reject,
+}
+
+impl Default for CreatureConfig {
+    fn default() -> Self {
+        Self {
+            generation_zero_size: 1000,
+            genome_length: 20,
+            initial_energy: 1000.0,
+            genome_sanitisation: GenomeSanitisation::Off,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnergyConfig {
+    /// The energy, per second, which a creature uses just by being alive.
+    pub base_cost: f32,
+    /// The energy, per second, used per unit of speed.
+    pub speed_cost: f32,
+    /// The energy, per second, used per radian per second of angular velocity.
+    pub angular_speed_cost: f32,
+    /// The energy a creature must have stored in order to reproduce.
+    pub reproduction_threshold: f32,
+    /// The energy a creature gives up when it reproduces.
+    pub reproduction_cost: f32,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self {
+            base_cost: 10.0,
+            speed_cost: 1.0,
+            angular_speed_cost: 1.0,
+            reproduction_threshold: 10000.0,
+            reproduction_cost: 5000.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FoodConfig {
+    /// The initial quantity of food to spawn. It is shared between the world and its patches in proportion to their
+    /// growth rates.
+    pub initial: u32,
+    /// The time, in seconds, between each growth of food.
+    pub replacement_interval: f64,
+    /// How close a creature must be to a piece of food in order to eat it.
+    pub consumption_distance: f32,
+    /// The kind of food which grows anywhere in the world, outside of the patches.
+    pub kind: FoodKind,
+    /// The number of pieces of food, per second, which grow anywhere in the world, outside of the patches.
+    pub growth_rate: f64,
+    /// The most food which can grow outside of the patches. Growth slows as it is approached. If this is missing, food
+    /// grows at the same rate however much there is.
+    pub capacity: Option<u32>,
+    /// Fertile areas of the world, where food grows at its own rate.
+    pub patches: Vec<FoodPatch>,
+    /// A cycle of seasons, which speeds up and slows down the growth of all food. If this is missing, growth is the
+    /// same all year round.
+    pub seasons: Option<Seasons>,
+    /// The settings for plants, which are the kind of food that grows by default.
+    pub plant: FoodKindConfig,
+    /// The settings for fruit, which only grows where it is configured to.
+    pub fruit: FoodKindConfig,
+    /// The settings for carrion, which is left behind by every creature which dies, unless its energy is zero, as it is by
+    /// default.
+    pub carrion: FoodKindConfig,
+    /// The settings for toxic food, which only grows where it is configured to.
+    pub toxic: FoodKindConfig,
+}
+
+impl FoodConfig {
+    /// Returns the settings for a kind of food.
+    pub fn kind(&self, kind: FoodKind) -> &FoodKindConfig {
+        match kind {
+            FoodKind::Plant => &self.plant,
+            FoodKind::Fruit => &self.fruit,
+            FoodKind::Carrion => &self.carrion,
+            FoodKind::Toxic => &self.toxic,
+        }
+    }
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        Self {
+            initial: 10000,
+            replacement_interval: 0.1,
+            consumption_distance: 1.0,
+            kind: FoodKind::Plant,
+            growth_rate: 10.0,
+            capacity: None,
+            patches: Vec::new(),
+            seasons: None,
+            plant: FoodKindConfig {
+                energy: 1000.0,
+                decay_rate: 0.0,
+            },
+            fruit: FoodKindConfig {
+                energy: 2000.0,
+                decay_rate: 20.0,
+            },
+            carrion: FoodKindConfig {
+                energy: 0.0,
+                decay_rate: 50.0,
+            },
+            toxic: FoodKindConfig {
+                energy: -1000.0,
+                decay_rate: 0.0,
+            },
+        }
+    }
+}
+
+/// The nutrition of a kind of food, and how quickly it is lost.
+///
+/// Both values must be given, as every kind has its own defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FoodKindConfig {
+    /// The energy which a creature gains from eating a fresh piece of the food. If this is negative, eating the food
+    /// costs energy instead.
+    ///
+    /// Toxic food must not be worth any energy. Food with no energy at all would be nothing to eat, so this can only be
+    /// zero for carrion, which is then not left behind, and for kinds which never grow.
+    pub energy: f32,
+    /// The energy, per second, which a piece of the food loses as it decays. Once it has none left, it disappears.
+    pub decay_rate: f32,
+}
+
+/// A circular area of the world, where food grows at its own rate, up to its own capacity.
+///
+/// Any food within the patch counts towards its capacity, wherever it grew.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FoodPatch {
+    /// The x coordinate of the centre of the patch, which must be within the world.
+    pub x: f32,
+    /// The y coordinate of the centre of the patch, which must be within the world.
+    pub y: f32,
+    /// The radius of the patch. Food never grows beyond the bounds of the world, even if the patch does.
+    pub radius: f32,
+    /// The number of pieces of food, per second, which grow in the patch.
+    pub growth_rate: f64,
+    /// The most food which the patch can hold. Growth slows as it is approached.
+    pub capacity: u32,
+    /// The kind of food which grows in the patch.
+    #[serde(default)]
+    pub kind: FoodKind,
+}
+
+/// A yearly cycle, in which the growth rate of food rises and falls like a sine wave.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Seasons {
+    /// The length of a year, in seconds.
+    pub period: f64,
+    /// How much growth rises in summer and falls in winter, as a proportion of the usual rate. At 1, nothing grows in
+    /// the depths of winter.
+    pub amplitude: f64,
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Self {
+            period: 600.0,
+            amplitude: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReproductionConfig {
+    /// Whether creatures reproduce alone or in pairs.
+    pub mode: ReproductionMode,
+    /// How the genomes of two parents are combined in sexual reproduction.
+    pub crossover: CrossoverMethod,
+    /// How close the centres of two creatures must be in order to mate. This can be at most twice the seeing distance.
+    /// Touching creatures are twice their radius apart.
+    pub mating_distance: f32,
+}
+
+impl Default for ReproductionConfig {
+    fn default() -> Self {
+        Self {
+            mode: ReproductionMode::Asexual,
+            crossover: CrossoverMethod::SinglePoint,
+            mating_distance: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReproductionMode {
+    /// A creature with enough energy has a child with a mutated copy of its own genome.
+    Asexual,
+    /// Two nearby creatures which both have enough energy have a child, whose genome is a mutated crossover of both of
+    /// theirs. Each parent gives up half of the reproduction cost.
+    Sexual,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpeciationConfig {
+    /// The time, in seconds, between each clustering of the population into species.
+    pub interval: f64,
+    /// How the population is clustered.
+    pub method: SpeciationMethod,
+    /// For threshold clustering, the distance within which a creature belongs to an existing species.
+    pub threshold: f32,
+    /// For k-medoids clustering, the number of species.
+    pub k: usize,
+    /// How the distance between two genomes is measured.
+    pub distance: DistanceConfig,
+}
+
+impl Default for SpeciationConfig {
+    fn default() -> Self {
+        Self {
+            interval: 10.0,
+            method: SpeciationMethod::Threshold,
+            threshold: 1.0,
+            k: 8,
+            distance: DistanceConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeciationMethod {
+    /// Each creature joins the species whose representative is nearest, if it is within the threshold. Otherwise, it
+    /// founds a new species. The number of species is unbounded.
+    Threshold,
+    /// The population is split into at most k species, each centred on one of its members.
+    KMedoids,
+}
+
+impl SimulationConfig {
+    /// Loads and validates a configuration from a TOML file.
+    ///
+    /// Any values which are missing from the file take their default values.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the config file {}.", path.display()))?;
+
+        let config: SimulationConfig = toml::from_str(&contents)
+            .with_context(|| format!("Could not parse the config file {}.", path.display()))?;
+
+        config
+            .validate()
+            .with_context(|| format!("The config file {} is invalid.", path.display()))?;
+
+        Ok(config)
+    }
+
+    /// Checks that every value is within its allowed range.
+    pub fn validate(&self) -> Result<(), InvalidConfig> {
+        let mut problems = Vec::new();
+
+        let mut check = |valid: bool, problem: String| {
+            if !valid {
+                problems.push(problem);
+            }
+        };
+
+        check(
+            self.world.bounds.is_finite() && self.world.bounds > 0.0,
+            format!(
+                "world.bounds must be positive, but was {}",
+                self.world.bounds
+            ),
+        );
+        check(
+            self.world.seeing_distance.is_finite() && self.world.seeing_distance > 0.0,
+            format!(
+                "world.seeing_distance must be positive, but was {}",
+                self.world.seeing_distance
+            ),
+        );
+
+        if let Topology::Repulsive(border) = &self.world.topology {
+            check(
+                border.strength.is_finite() && border.strength >= 0.0,
+                format!(
+                    "world.topology.strength must not be negative, but was {}",
+                    border.strength
+                ),
+            );
+        }
+
+        check(
+            self.time.fixed_update_frequency.is_finite() && self.time.fixed_update_frequency > 0.0,
+            format!(
+                "time.fixed_update_frequency must be positive, but was {}",
+                self.time.fixed_update_frequency
+            ),
+        );
+        check(
+            self.time.brain_update_frequency.is_finite() && self.time.brain_update_frequency > 0.0,
+            format!(
+                "time.brain_update_frequency must be positive, but was {}",
+                self.time.brain_update_frequency
+            ),
+        );
+
+        for (name, value) in [
+            ("physics.mass", self.physics.mass),
+            ("physics.max_speed", self.physics.max_speed),
+            ("physics.max_angular_speed", self.physics.max_angular_speed),
+            ("physics.radius", self.physics.radius),
+        ] {
+            check(
+                value.is_finite() && value > 0.0,
+                format!("{name} must be positive, but was {value}"),
+            );
+        }
+
+        for (name, value) in [
+            ("physics.thrust", self.physics.thrust),
+            ("physics.torque", self.physics.torque),
+            ("physics.linear_drag", self.physics.linear_drag),
+            ("physics.angular_drag", self.physics.angular_drag),
+        ] {
+            check(
+                value.is_finite() && value >= 0.0,
+                format!("{name} must not be negative, but was {value}"),
+            );
+        }
+
+        check(
+            (0.0..=1.0).contains(&self.physics.restitution),
+            format!(
+                "physics.restitution must be between 0 and 1, but was {}",
+                self.physics.restitution
+            ),
+        );
+        // Collisions are found in the spatial index, which only covers twice the seeing distance.
+        check(
+            self.physics.radius <= self.world.seeing_distance,
+            format!(
+                "physics.radius ({}) must not be greater than world.seeing_distance ({})",
+                self.physics.radius, self.world.seeing_distance
+            ),
+        );
+        check(
+            self.creatures.initial_energy.is_finite() && self.creatures.initial_energy > 0.0,
+            format!(
+                "creatures.initial_energy must be positive, but was {}",
+                self.creatures.initial_energy
+            ),
+        );
+
+        for (name, cost) in [
+            ("energy.base_cost", self.energy.base_cost),
+            ("energy.speed_cost", self.energy.speed_cost),
+            ("energy.angular_speed_cost", self.energy.angular_speed_cost),
+            ("energy.reproduction_cost", self.energy.reproduction_cost),
+        ] {
+            check(
+                cost.is_finite() && cost >= 0.0,
+                format!("{name} must not be negative, but was {cost}"),
+            );
+        }
+
+        check(
+            self.energy.reproduction_threshold.is_finite()
+                && self.energy.reproduction_threshold > 0.0,
+            format!(
+                "energy.reproduction_threshold must be positive, but was {}",
+                self.energy.reproduction_threshold
+            ),
+        );
+        check(
+            self.energy.reproduction_cost <= self.energy.reproduction_threshold,
+            format!(
+                "energy.reproduction_cost ({}) must not be greater than energy.reproduction_threshold ({})",
+                self.energy.reproduction_cost, self.energy.reproduction_threshold
+            ),
+        );
+        check(
+            self.food.replacement_interval.is_finite() && self.food.replacement_interval > 0.0,
+            format!(
+                "food.replacement_interval must be positive, but was {}",
+                self.food.replacement_interval
+            ),
+        );
+        check(
+            self.food.growth_rate.is_finite() && self.food.growth_rate >= 0.0,
+            format!(
+                "food.growth_rate must not be negative, but was {}",
+                self.food.growth_rate
+            ),
+        );
+
+        for (index, patch) in self.food.patches.iter().enumerate() {
+            check(
+                patch.x.abs() <= self.world.bounds && patch.y.abs() <= self.world.bounds,
+                format!(
+                    "food.patches[{index}] must be centred within the world, but was at ({}, {})",
+                    patch.x, patch.y
+                ),
+            );
+            check(
+                patch.radius.is_finite() && patch.radius > 0.0,
+                format!(
+                    "food.patches[{index}].radius must be positive, but was {}",
+                    patch.radius
+                ),
+            );
+            check(
+                patch.growth_rate.is_finite() && patch.growth_rate >= 0.0,
+                format!(
+                    "food.patches[{index}].growth_rate must not be negative, but was {}",
+                    patch.growth_rate
+                ),
+            );
+        }
+
+        if let Some(seasons) = &self.food.seasons {
+            check(
+                seasons.period.is_finite() && seasons.period > 0.0,
+                format!(
+                    "food.seasons.period must be positive, but was {}",
+                    seasons.period
+                ),
+            );
+            check(
+                (0.0..=1.0).contains(&seasons.amplitude),
+                format!(
+                    "food.seasons.amplitude must be between 0 and 1, but was {}",
+                    seasons.amplitude
+                ),
+            );
+        }
+
+        for kind in FoodKind::ALL {
+            let settings = self.food.kind(kind);
+
+            check(
+                settings.energy.is_finite(),
+                format!(
+                    "food.{kind}.energy must be finite, but was {}",
+                    settings.energy
+                ),
+            );
+
+            let grows =
+                self.food.kind == kind || self.food.patches.iter().any(|patch| patch.kind == kind);
+
+            check(
+                !grows || settings.energy != 0.0,
+                format!("food.{kind}.energy must not be zero, as {kind} is configured to grow"),
+            );
+            check(
+                settings.decay_rate.is_finite() && settings.decay_rate >= 0.0,
+                format!(
+                    "food.{kind}.decay_rate must not be negative, but was {}",
+                    settings.decay_rate
+                ),
+            );
+        }
+
+        check(
+            self.food.toxic.energy.is_nan() || self.food.toxic.energy <= 0.0,
+            format!(
+                "food.toxic.energy must not be positive, but was {}",
+                self.food.toxic.energy
+            ),
+        );
+        check(
+            self.food.consumption_distance.is_finite() && self.food.consumption_distance >= 0.0,
+            format!(
+                "food.consumption_distance must not be negative, but was {}",
+                self.food.consumption_distance
+            ),
+        );
+        check(
+            self.reproduction.mating_distance.is_finite()
+                && self.reproduction.mating_distance >= 0.0,
+            format!(
+                "reproduction.mating_distance must not be negative, but was {}",
+                self.reproduction.mating_distance
+            ),
+        );
+        // Mates are found in the spatial index, which only covers this distance.
+        check(
+            self.reproduction.mating_distance <= self.world.seeing_distance * 2.0,
+            format!(
+                "reproduction.mating_distance ({}) must not be greater than twice world.seeing_distance ({})",
+                self.reproduction.mating_distance, self.world.seeing_distance
+            ),
+        );
+
+        match &self.mutation {
+            MutationConfig::BitFlip(model) => {
+                check(
+                    (0.0..=1.0).contains(&model.rate),
+                    format!(
+                        "mutation.rate must be between 0 and 1, but was {}",
+                        model.rate
+                    ),
+                );
+            }
+            MutationConfig::GaussianCreep(model) => {
+                for (name, probability) in [
+                    ("mutation.weight_probability", model.weight_probability),
+                    ("mutation.id_rate", model.id_rate),
+                ] {
+                    check(
+                        (0.0..=1.0).contains(&probability),
+                        format!("{name} must be between 0 and 1, but was {probability}"),
+                    );
+                }
+
+                check(
+                    model.sigma.is_finite() && model.sigma >= 0.0,
+                    format!(
+                        "mutation.sigma must not be negative, but was {}",
+                        model.sigma
+                    ),
+                );
+                check(
+                    model.min_weight.is_finite()
+                        && model.max_weight.is_finite()
+                        && model.min_weight <= model.max_weight,
+                    format!(
+                        "mutation.min_weight ({}) and mutation.max_weight ({}) must be finite, with the minimum no greater than the maximum",
+                        model.min_weight, model.max_weight
+                    ),
+                );
+            }
+        }
+
+        let structure = &self.structural_mutation;
+
+        for (name, probability) in [
+            (
+                "structural_mutation.insertion_probability",
+                structure.insertion_probability,
+            ),
+            (
+                "structural_mutation.deletion_probability",
+                structure.deletion_probability,
+            ),
+            (
+                "structural_mutation.duplication_probability",
+                structure.duplication_probability,
+            ),
+            (
+                "structural_mutation.swap_probability",
+                structure.swap_probability,
+            ),
+        ] {
+            check(
+                (0.0..=1.0).contains(&probability),
+                format!("{name} must be between 0 and 1, but was {probability}"),
+            );
+        }
+
+        check(
+            (structure.min_length..=structure.max_length).contains(&self.creatures.genome_length),
+            format!(
+                "creatures.genome_length ({}) must be between structural_mutation.min_length ({}) and structural_mutation.max_length ({})",
+                self.creatures.genome_length, structure.min_length, structure.max_length
+            ),
+        );
+        check(
+            (1..=128).contains(&self.brain.max_internal_neurons),
+            format!(
+                "brain.max_internal_neurons must be between 1 and 128, but was {}",
+                self.brain.max_internal_neurons
+            ),
+        );
+
+        let speciation = &self.speciation;
+
+        check(
+            speciation.interval.is_finite() && speciation.interval > 0.0,
+            format!(
+                "speciation.interval must be positive, but was {}",
+                speciation.interval
+            ),
+        );
+        check(
+            speciation.threshold.is_finite() && speciation.threshold >= 0.0,
+            format!(
+                "speciation.threshold must not be negative, but was {}",
+                speciation.threshold
+            ),
+        );
+        check(
+            speciation.k >= 1,
+            format!("speciation.k must be at least 1, but was {}", speciation.k),
+        );
+
+        for (name, coefficient) in [
+            (
+                "speciation.distance.id_coefficient",
+                speciation.distance.id_coefficient,
+            ),
+            (
+                "speciation.distance.weight_coefficient",
+                speciation.distance.weight_coefficient,
+            ),
+            (
+                "speciation.distance.length_coefficient",
+                speciation.distance.length_coefficient,
+            ),
+        ] {
+            check(
+                coefficient.is_finite() && coefficient >= 0.0,
+                format!("{name} must not be negative, but was {coefficient}"),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidConfig { problems })
+        }
+    }
+}
+
+/// An error returned when one or more configuration values are out of range.
+#[derive(Debug)]
+pub struct InvalidConfig {
+    problems: Vec<String>,
+}
+
+impl InvalidConfig {
+    /// Returns a description of each invalid value.
+    pub fn problems(&self) -> &Vec<String> {
+        &self.problems
+    }
+}
+
+impl Display for InvalidConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The configuration is invalid:")?;
+
+        for problem in &self.problems {
+            write!(f, "\n  - {problem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for InvalidConfig {}
@@ -1,163 +1,226 @@
 pub mod vision;
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::prelude::*;
 use rand::Rng;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
 
 use super::{
-    AngularVelocity, BRAIN_UPDATE_FREQUENCY, GENERATION_ZERO_SIZE, GENOME_LENGTH, INITIAL_ENERGY,
-    MUTATION_RATE, Velocity, WORLD_BOUNDS, spatial_index::SpatialIndex,
+    AngularVelocity, Contact, Force, GenomeSanitisation, ReproductionMode, SimulationConfig,
+    SimulationRng, SimulationSet, Torque, Velocity, every,
+    food::{Food, FoodBundle, FoodKind},
+    neurons::{BrainSet, BuiltinNeuronsPlugin},
+    physics::{clear_contacts, clear_forces, integrate_forces, resolve_collisions},
+    snapshot::RestoredFromSnapshot,
+    spatial_index::{ObjectCategory, SpatialIndex},
+    topology::confine_to_world,
 };
 use crate::model::creature::{
-    brain::{ActionOutput, Activation, Brain, InternalNeuron, Neuron, SensoryInputs},
+    brain::{ActionOutputs, Brain, NeuronRegistry, SensoryInputs},
     genome::Genome,
 };
+use vision::LinesOfSight;
 
 #[derive(Bundle)]
 pub struct CreatureBundle {
-    pub mesh: Mesh2d,
-    pub mesh_material: MeshMaterial2d<ColorMaterial>,
     pub transform: Transform,
-    pub visibility: Visibility,
     pub velocity: Velocity,
     pub angular_velocity: AngularVelocity,
+    pub force: Force,
+    pub torque: Torque,
+    pub contact: Contact,
     pub energy: Energy,
     pub brain: Brain,
     pub genome: Genome,
     pub age: Age,
+    pub lineage: Lineage,
+    pub lines_of_sight: LinesOfSight,
+    pub sensory_inputs: SensoryInputs,
+    pub action_outputs: ActionOutputs,
 }
 
 pub struct CreaturePlugin;
 
 impl Plugin for CreaturePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_generation_zero);
+        app.init_resource::<SimulationConfig>();
+        app.init_resource::<SimulationRng>();
+        app.init_resource::<CreatureIds>();
+        app.add_event::<CreatureBorn>();
+        app.add_event::<CreatureDied>();
+
+        // The built-in senses and actions come before any others, so that they always have the same ids.
+        app.add_plugins(BuiltinNeuronsPlugin);
+
+        let config = app.world().resource::<SimulationConfig>();
+        let brain_update_frequency = config.time.brain_update_frequency;
+        let reproduction_mode = config.reproduction.mode;
+
+        app.add_systems(
+            Startup,
+            spawn_generation_zero
+                .run_if(not(resource_exists::<RestoredFromSnapshot>))
+                .in_set(SimulationSet::Creatures),
+        );
+
+        app.configure_sets(
+            FixedUpdate,
+            (
+                BrainSet::Perceive,
+                BrainSet::Sense,
+                BrainSet::Think,
+                BrainSet::Act,
+            )
+                .chain()
+                .in_set(SimulationSet::Creatures)
+                .before(deduct_energy),
+        );
+
+        for set in [
+            BrainSet::Perceive,
+            BrainSet::Sense,
+            BrainSet::Think,
+            BrainSet::Act,
+        ] {
+            app.configure_sets(FixedUpdate, set.run_if(every(1.0 / brain_update_frequency)));
+        }
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                vision::update_lines_of_sight.in_set(BrainSet::Perceive),
+                (clear_forces, clear_contacts, execute_creature_decisions).in_set(BrainSet::Think),
+            ),
+        );
 
         app.add_systems(
             FixedUpdate,
             (
                 deduct_energy,
                 kill_creatures,
-                have_babies,
+                have_babies.run_if(move || reproduction_mode == ReproductionMode::Asexual),
+                mate.run_if(move || reproduction_mode == ReproductionMode::Sexual),
                 update_ages,
+                integrate_forces,
                 update_translations,
+                resolve_collisions,
+                confine_to_world,
                 update_rotations,
             )
-                .chain(),
-        );
-
-        app.add_systems(
-            Update,
-            execute_creature_decisions.run_if(on_timer(Duration::from_secs_f64(
-                1.0 / BRAIN_UPDATE_FREQUENCY,
-            ))),
+                .chain()
+                .in_set(SimulationSet::Creatures),
         );
     }
 }
 
 fn spawn_creature(
     commands: &mut Commands,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
-    meshes: &mut ResMut<Assets<Mesh>>,
     transform: Transform,
     genome: Genome,
     brain: Brain,
-) {
-    let body = meshes.add(Circle::new(1.0));
-
-    commands.spawn(CreatureBundle {
-        mesh: Mesh2d(body),
-        mesh_material: MeshMaterial2d(materials.add(Color::linear_rgb(1.0, 0.0, 0.0))),
-        transform,
-        visibility: Visibility::Visible,
-        velocity: Velocity {
-            value: Vec2::default(),
-        },
-        angular_velocity: AngularVelocity { value: 0.0 },
-        energy: Energy {
-            value: INITIAL_ENERGY,
+    energy: f32,
+    lineage: Lineage,
+) -> Entity {
+    commands
+        .spawn(CreatureBundle {
+            transform,
+            velocity: Velocity {
+                value: Vec2::default(),
+            },
+            angular_velocity: AngularVelocity { value: 0.0 },
+            force: Force::default(),
+            torque: Torque::default(),
+            contact: Contact::default(),
+            energy: Energy { value: energy },
+            brain,
+            genome,
+            age: Age { value: 0.0 },
+            lineage,
+            lines_of_sight: LinesOfSight::default(),
+            sensory_inputs: SensoryInputs::default(),
+            action_outputs: ActionOutputs::default(),
+        })
+        .id()
+}
+
+/// Applies the configured sanitisation to a genome which is about to have a brain built from it.
+///
+/// Returns nothing if the genome was rejected, in which case the creature should not be spawned.
+pub(crate) fn sanitise(
+    genome: Genome,
+    config: &SimulationConfig,
+    registry: &NeuronRegistry,
+) -> Option<Genome> {
+    let sanitisation = config.creatures.genome_sanitisation;
+
+    if sanitisation == GenomeSanitisation::Off {
+        return Some(genome);
+    }
+
+    match genome.validate(&config.brain, registry) {
+        Err(invalid) if invalid.is_corrupt() => match sanitisation {
+            GenomeSanitisation::Repair => Some(genome.repaired()),
+            _ => None,
         },
-        brain,
-        genome,
-        age: Age { value: 0.0 },
-    });
+        _ => Some(genome),
+    }
 }
 
 fn spawn_generation_zero(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut generator: ResMut<SimulationRng>,
+    mut ids: ResMut<CreatureIds>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+    registry: Res<NeuronRegistry>,
 ) {
-    let mut generator = rand::thread_rng();
+    let world_bounds = config.world.bounds;
 
-    for _ in 0..GENERATION_ZERO_SIZE {
+    for _ in 0..config.creatures.generation_zero_size {
         let transform = Transform {
             translation: Vec3::new(
-                generator.gen_range(-WORLD_BOUNDS..=WORLD_BOUNDS),
-                generator.gen_range(-WORLD_BOUNDS..=WORLD_BOUNDS),
+                generator.gen_range(-world_bounds..=world_bounds),
+                generator.gen_range(-world_bounds..=world_bounds),
                 0.0,
             ),
             ..default()
         };
 
-        let genome = Genome::random(GENOME_LENGTH);
-        let brain = Brain::new(&genome);
+        let genome = Genome::random(config.creatures.genome_length, &mut *generator);
+
+        let Some(genome) = sanitise(genome, &config, &registry) else {
+            continue;
+        };
+
+        let brain = Brain::new(&genome, &config.brain, &registry);
+
+        let lineage = Lineage {
+            id: ids.allocate(),
+            parent: None,
+            mate: None,
+            generation: 0,
+            birth_time: time.elapsed_secs_f64(),
+        };
 
         spawn_creature(
             &mut commands,
-            &mut materials,
-            &mut meshes,
             transform,
             genome,
             brain,
+            config.creatures.initial_energy,
+            lineage,
         );
     }
 }
 
-fn execute_creature_decisions(
-    mut query: Query<(
-        &Brain,
-        &Transform,
-        &mut Velocity,
-        &mut AngularVelocity,
-        &Energy,
-        &Age,
-    )>,
-    spatial_index: Res<SpatialIndex>,
-) {
-    for (brain, transform, mut velocity, mut angular_velocity, energy, age) in &mut query {
-        let mut internal_activation_cache: HashMap<Arc<InternalNeuron>, f32> = HashMap::new();
+/// Evaluates the brain of every creature, using the inputs written by the sensors, and leaves the activations of its
+/// action neurons for the actuators.
+fn execute_creature_decisions(mut query: Query<(&mut Brain, &SensoryInputs, &mut ActionOutputs)>) {
+    for (mut brain, sensory_inputs, mut action_outputs) in &mut query {
+        action_outputs.clear();
 
-        let lines_of_sight = vision::compute_vision(transform, &spatial_index.index);
-
-        let sensory_inputs = SensoryInputs {
-            age: age.value,
-            speed: velocity.value.length(),
-            angular_velocity: angular_velocity.value,
-            lines_of_sight,
-            stored_energy: energy.value,
-        };
-
-        for action_neuron in brain.neurons().iter().filter_map(|neuron| match neuron {
-            Neuron::Action(action_neuron) => Some(action_neuron),
-            _ => None,
-        }) {
-            let activation =
-                action_neuron.activation(&mut internal_activation_cache, &sensory_inputs);
-
-            match action_neuron.output() {
-                ActionOutput::Acceleration => {
-                    let angle = transform.rotation.to_euler(EulerRot::XYZ).2;
-
-                    let acceleration =
-                        Vec2::new(activation * angle.cos(), activation * angle.sin());
-
-                    velocity.value += acceleration;
-                }
-                ActionOutput::AngularAcceleration => {
-                    angular_velocity.value += activation;
-                }
-            }
+        for (output, activation) in brain.evaluate(sensory_inputs) {
+            action_outputs.set(&output, activation);
         }
     }
 }
@@ -183,52 +246,238 @@ fn update_ages(mut query: Query<&mut Age>, time: Res<Time<Fixed>>) {
 fn deduct_energy(
     mut query: Query<(&mut Energy, &Velocity, &AngularVelocity)>,
     time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
 ) {
+    let costs = &config.energy;
+
     for (mut energy, velocity, angular_velocity) in &mut query {
-        // TODO: export constants for multipliers of the different terms in this function, fine tune.
-        energy.value -=
-            (10.0 + velocity.value.length() + angular_velocity.value.abs()) * time.delta_secs();
+        energy.value -= (costs.base_cost
+            + costs.speed_cost * velocity.value.length()
+            + costs.angular_speed_cost * angular_velocity.value.abs())
+            * time.delta_secs();
     }
 }
 
-fn kill_creatures(query: Query<(&Energy, Entity)>, mut commands: Commands) {
-    for (energy, entity) in &query {
+/// Removes every creature which has run out of energy, and leaves carrion in its place unless carrion is worthless.
+fn kill_creatures(
+    query: Query<(&Energy, &Lineage, &Transform, Entity)>,
+    mut commands: Commands,
+    mut deaths: EventWriter<CreatureDied>,
+    config: Res<SimulationConfig>,
+) {
+    for (energy, lineage, transform, entity) in &query {
         if energy.value <= 0.0 {
             commands.entity(entity).despawn();
+
+            if config.food.carrion.energy != 0.0 {
+                commands.spawn(FoodBundle {
+                    transform: Transform::from_translation(transform.translation.with_z(-1.0)),
+                    food: Food::fresh(FoodKind::Carrion, &config.food),
+                });
+            }
+
+            deaths.send(CreatureDied {
+                entity,
+                id: lineage.id,
+            });
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn have_babies(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut query: Query<(&mut Energy, &Genome, &Transform), With<Brain>>,
+    mut query: Query<(Entity, &mut Energy, &Genome, &Transform, &Lineage), With<Brain>>,
+    mut generator: ResMut<SimulationRng>,
+    mut ids: ResMut<CreatureIds>,
+    mut births: EventWriter<CreatureBorn>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+    registry: Res<NeuronRegistry>,
 ) {
-    for (mut energy, genome, transform) in &mut query {
-        if energy.value >= 10000.0 {
-            energy.value -= 5000.0;
+    for (parent, mut energy, genome, transform, lineage) in &mut query {
+        if energy.value >= config.energy.reproduction_threshold {
+            energy.value -= config.energy.reproduction_cost;
+
+            let new_genome = genome
+                .mutated(&config.mutation, &mut *generator)
+                .mutated_structurally(&config.structural_mutation, &mut *generator);
 
-            let new_genome = genome.mutated(MUTATION_RATE);
-            let new_brain = Brain::new(&new_genome);
+            let Some(new_genome) = sanitise(new_genome, &config, &registry) else {
+                continue;
+            };
+
+            let new_brain = Brain::new(&new_genome, &config.brain, &registry);
             let mut new_transform = Transform {
                 translation: transform.translation,
                 ..default()
             };
-            new_transform.translation.x += 1.0;
+            // The child is placed just touching its parent.
+            new_transform.translation.x += 2.0 * config.physics.radius;
+
+            let new_lineage = Lineage {
+                id: ids.allocate(),
+                parent: Some(lineage.id),
+                mate: None,
+                generation: lineage.generation + 1,
+                birth_time: time.elapsed_secs_f64(),
+            };
 
-            spawn_creature(
+            let child = spawn_creature(
                 &mut commands,
-                &mut materials,
-                &mut meshes,
                 new_transform,
                 new_genome,
                 new_brain,
+                config.creatures.initial_energy,
+                new_lineage,
             );
+
+            births.send(CreatureBorn {
+                parent,
+                mate: None,
+                child,
+            });
         }
     }
 }
 
+/// Pairs up nearby creatures which both have enough energy to reproduce, and gives each pair a child.
+///
+/// Each creature mates at most once per timestep, with the nearest willing partner in range.
+#[allow(clippy::too_many_arguments)]
+fn mate(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Energy, &Genome, &Transform, &Lineage), With<Brain>>,
+    spatial_index: Res<SpatialIndex>,
+    mut generator: ResMut<SimulationRng>,
+    mut ids: ResMut<CreatureIds>,
+    mut births: EventWriter<CreatureBorn>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+    registry: Res<NeuronRegistry>,
+) {
+    let threshold = config.energy.reproduction_threshold;
+    let mating_distance = config.reproduction.mating_distance;
+
+    let willing: Vec<Entity> = query
+        .iter()
+        .filter(|(_, energy, ..)| energy.value >= threshold)
+        .map(|(entity, ..)| entity)
+        .collect();
+
+    // The creatures which are willing and have not yet found a partner. The vector above decides the order in which
+    // partners are sought, as iterating over a set would not be deterministic.
+    let mut unpaired: HashSet<Entity> = willing.iter().copied().collect();
+
+    let mut pairs = Vec::new();
+
+    for entity in willing {
+        if !unpaired.remove(&entity) {
+            continue;
+        }
+
+        let Ok((_, _, _, transform, _)) = query.get(entity) else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+
+        let partner = spatial_index
+            .neighbourhood(position.x, position.y)
+            .filter(|object| {
+                object.category == ObjectCategory::Creature && unpaired.contains(&object.entity)
+            })
+            .map(|object| {
+                let (object_x, object_y) =
+                    spatial_index.nearest_image(position.x, position.y, object.x, object.y);
+
+                (
+                    object.entity,
+                    position.distance(Vec2::new(object_x, object_y)),
+                )
+            })
+            .filter(|(_, distance)| *distance <= mating_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(partner, _)| partner);
+
+        if let Some(partner) = partner {
+            unpaired.remove(&partner);
+            pairs.push((entity, partner));
+        }
+    }
+
+    for (parent, mate) in pairs {
+        let Ok(
+            [
+                (_, mut parent_energy, parent_genome, parent_transform, parent_lineage),
+                (_, mut mate_energy, mate_genome, _, mate_lineage),
+            ],
+        ) = query.get_many_mut([parent, mate])
+        else {
+            continue;
+        };
+
+        parent_energy.value -= config.energy.reproduction_cost / 2.0;
+        mate_energy.value -= config.energy.reproduction_cost / 2.0;
+
+        let new_genome = parent_genome
+            .crossover(mate_genome, config.reproduction.crossover, &mut *generator)
+            .mutated(&config.mutation, &mut *generator)
+            .mutated_structurally(&config.structural_mutation, &mut *generator);
+
+        let Some(new_genome) = sanitise(new_genome, &config, &registry) else {
+            continue;
+        };
+
+        let new_brain = Brain::new(&new_genome, &config.brain, &registry);
+        let mut new_transform = Transform {
+            translation: parent_transform.translation,
+            ..default()
+        };
+        // The child is placed just touching its parent.
+        new_transform.translation.x += 2.0 * config.physics.radius;
+
+        let new_lineage = Lineage {
+            id: ids.allocate(),
+            parent: Some(parent_lineage.id),
+            mate: Some(mate_lineage.id),
+            generation: parent_lineage.generation.max(mate_lineage.generation) + 1,
+            birth_time: time.elapsed_secs_f64(),
+        };
+
+        let child = spawn_creature(
+            &mut commands,
+            new_transform,
+            new_genome,
+            new_brain,
+            config.creatures.initial_energy,
+            new_lineage,
+        );
+
+        births.send(CreatureBorn {
+            parent,
+            mate: Some(mate),
+            child,
+        });
+    }
+}
+
+/// Sent whenever a creature reproduces. Creatures in generation zero are not born, so this is not sent for them.
+#[derive(Event)]
+pub struct CreatureBorn {
+    pub parent: Entity,
+    /// The second parent, if the child was born through sexual reproduction.
+    pub mate: Option<Entity>,
+    pub child: Entity,
+}
+
+/// Sent whenever a creature dies. The entity has already been despawned by the time this is read.
+#[derive(Event)]
+pub struct CreatureDied {
+    pub entity: Entity,
+    pub id: CreatureId,
+}
+
 #[derive(Component)]
 pub struct Energy {
     pub value: f32,
@@ -238,3 +487,42 @@ pub struct Energy {
 pub struct Age {
     pub value: f32,
 }
+
+/// An identifier which is unique to a creature for the whole of a run, unlike its [Entity], which may be reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CreatureId(pub u64);
+
+impl Display for CreatureId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where a creature came from.
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct Lineage {
+    pub id: CreatureId,
+    /// The creature which this creature was born from, or nothing if it was part of generation zero.
+    pub parent: Option<CreatureId>,
+    /// The second parent, if the creature was born through sexual reproduction.
+    pub mate: Option<CreatureId>,
+    /// The length of the longest line of ancestors between this creature and generation zero.
+    pub generation: u32,
+    /// The simulated time, in seconds, at which the creature was born.
+    pub birth_time: f64,
+}
+
+/// Hands out creature IDs in order.
+#[derive(Resource, Default)]
+pub(crate) struct CreatureIds {
+    pub(crate) next: u64,
+}
+
+impl CreatureIds {
+    fn allocate(&mut self) -> CreatureId {
+        let id = CreatureId(self.next);
+        self.next += 1;
+        id
+    }
+}
@@ -1,10 +1,97 @@
 use bevy::prelude::*;
-use std::collections::HashMap;
 use std::f32::consts::{E, PI};
 
-use crate::model::creature::brain::LinesOfSight;
-use crate::simulation::SEEING_DISTANCE;
-use crate::simulation::spatial_index::{ObjectCategory, get_cell_coordinates};
+use crate::simulation::{
+    FoodKind, SimulationConfig,
+    spatial_index::{ObjectCategory, SpatialIndex},
+};
+
+/// How close the nearest creature, the nearest food, and the nearest food of each kind are along each of a creature's
+/// eyelines, as of the latest brain update. Each value is one for an object at the creature's centre, falling away
+/// towards zero with distance, and is zero if nothing is in sight.
+#[derive(Component, Default)]
+pub struct LinesOfSight {
+    pub left_creature: f32,
+    pub left_food: f32,
+    pub middle_creature: f32,
+    pub middle_food: f32,
+    pub right_creature: f32,
+    pub right_food: f32,
+    pub left_plant: f32,
+    pub left_fruit: f32,
+    pub left_carrion: f32,
+    pub left_toxic: f32,
+    pub middle_plant: f32,
+    pub middle_fruit: f32,
+    pub middle_carrion: f32,
+    pub middle_toxic: f32,
+    pub right_plant: f32,
+    pub right_fruit: f32,
+    pub right_carrion: f32,
+    pub right_toxic: f32,
+}
+
+impl LinesOfSight {
+    /// Returns the value which an object seen along an eyeline brightens, and, for food, the value for its kind too.
+    fn values_mut(
+        &mut self,
+        eye_angle: &EyeAngle,
+        category: ObjectCategory,
+    ) -> (&mut f32, Option<&mut f32>) {
+        let Self {
+            left_creature,
+            left_food,
+            middle_creature,
+            middle_food,
+            right_creature,
+            right_food,
+            left_plant,
+            left_fruit,
+            left_carrion,
+            left_toxic,
+            middle_plant,
+            middle_fruit,
+            middle_carrion,
+            middle_toxic,
+            right_plant,
+            right_fruit,
+            right_carrion,
+            right_toxic,
+        } = self;
+
+        let (creature, food, [plant, fruit, carrion, toxic]) = match eye_angle {
+            EyeAngle::Left => (
+                left_creature,
+                left_food,
+                [left_plant, left_fruit, left_carrion, left_toxic],
+            ),
+            EyeAngle::Middle => (
+                middle_creature,
+                middle_food,
+                [middle_plant, middle_fruit, middle_carrion, middle_toxic],
+            ),
+            EyeAngle::Right => (
+                right_creature,
+                right_food,
+                [right_plant, right_fruit, right_carrion, right_toxic],
+            ),
+        };
+
+        match category {
+            ObjectCategory::Creature => (creature, None),
+            ObjectCategory::Food(kind) => {
+                let kind_value = match kind {
+                    FoodKind::Plant => plant,
+                    FoodKind::Fruit => fruit,
+                    FoodKind::Carrion => carrion,
+                    FoodKind::Toxic => toxic,
+                };
+
+                (food, Some(kind_value))
+            }
+        }
+    }
+}
 
 enum EyeAngle {
     Left,
@@ -26,154 +113,140 @@ pub struct VisibleObject {
     pub entity: Entity,
 }
 
+/// Looks along the eyelines of every creature.
+pub(super) fn update_lines_of_sight(
+    mut query: Query<(&mut LinesOfSight, &Transform)>,
+    spatial_index: Res<SpatialIndex>,
+    config: Res<SimulationConfig>,
+) {
+    for (mut lines_of_sight, transform) in &mut query {
+        *lines_of_sight = compute_vision(transform, &spatial_index, config.world.seeing_distance);
+    }
+}
+
 pub fn compute_vision(
     transform: &Transform,
-    spatial_index: &HashMap<(i32, i32), Vec<VisibleObject>>,
+    spatial_index: &SpatialIndex,
+    seeing_distance: f32,
 ) -> LinesOfSight {
     let mut lines_of_sight = LinesOfSight { ..default() };
 
     let (creature_x, creature_y) = (transform.translation.x, transform.translation.y);
 
-    let (cell_x, cell_y) = get_cell_coordinates(creature_x, creature_y);
-
-    // This forms a 3 by 3 grid, centred at the current cell.
-    let cells_to_search = [
-        (cell_x, cell_y),
-        (cell_x - SEEING_DISTANCE * 2, cell_y),
-        (cell_x + SEEING_DISTANCE * 2, cell_y),
-        (cell_x, cell_y - SEEING_DISTANCE * 2),
-        (cell_x, cell_y + SEEING_DISTANCE * 2),
-        (cell_x - SEEING_DISTANCE * 2, cell_y - SEEING_DISTANCE * 2),
-        (cell_x + SEEING_DISTANCE * 2, cell_y - SEEING_DISTANCE * 2),
-        (cell_x - SEEING_DISTANCE * 2, cell_y + SEEING_DISTANCE * 2),
-        (cell_x + SEEING_DISTANCE * 2, cell_y + SEEING_DISTANCE * 2),
-    ];
-
-    for cell in cells_to_search {
-        let objects = spatial_index.get(&cell);
-
-        if let Some(objects) = objects {
-            for object in objects {
-                if object.x == creature_x && object.y == creature_y {
+    for object in spatial_index.neighbourhood(creature_x, creature_y) {
+        if object.x == creature_x && object.y == creature_y {
+            continue;
+        }
+
+        // In a toroidal world, the object may be seen across the seam.
+        let (object_x, object_y) =
+            spatial_index.nearest_image(creature_x, creature_y, object.x, object.y);
+
+        for eye_angle in EYE_ANGLES {
+            let global_eye_angle = eye_angle.1 + transform.rotation.to_euler(EulerRot::XYZ).2;
+
+            let eyeline_is_vertical = (global_eye_angle.abs() % PI - PI / 2.0).abs() < f32::EPSILON;
+
+            let mut intersection_coordinates: Vec<(f32, f32)> = Vec::new();
+
+            if eyeline_is_vertical {
+                // The intersection of a vertical eyeline and the object can be re-arranged into a quadratic in the form ay^2 + by + c
+                let a = 1.0;
+                let b = -2.0 * object_y;
+                let c = object_x.powi(2) + object_y.powi(2) + creature_x.powi(2)
+                    - 2.0 * object_x * creature_x
+                    - object.radius;
+
+                let discriminant = b.powi(2) - 4.0 * a * c;
+
+                let mut y_values: Vec<f32> = Vec::new();
+
+                if discriminant < 0.0 {
                     continue;
+                } else if discriminant.abs() < 1e-6 {
+                    y_values.push(-b / (2.0 * a));
+                } else if discriminant > 0.0 {
+                    y_values.push((-b + discriminant.sqrt()) / (2.0 * a));
+                    y_values.push((-b - discriminant.sqrt()) / (2.0 * a));
+                }
+
+                intersection_coordinates.extend(
+                    y_values
+                        .iter()
+                        .map(|intersection_y| (creature_x, *intersection_y)),
+                );
+            } else {
+                let eyeline_gradient = global_eye_angle.tan();
+                let eyeline_y_intercept =
+                    transform.translation.y - eyeline_gradient * transform.translation.x;
+
+                // The intersection of the eyeline and the object can be re-arranged into a quadratic in the form ax^2 + bx + c
+                let a = eyeline_gradient.powi(2) + 1.0;
+                let b = 2.0 * eyeline_gradient * eyeline_y_intercept
+                    - 2.0 * eyeline_gradient * object_y
+                    - 2.0 * object_x;
+                let c = object_x.powi(2) + object_y.powi(2) + eyeline_y_intercept.powi(2)
+                    - 2.0 * object_y * eyeline_y_intercept
+                    - object.radius;
+
+                let discriminant = b.powi(2) - 4.0 * a * c;
+
+                let mut x_values: Vec<f32> = Vec::new();
+
+                if discriminant < 0.0 {
+                    continue;
+                } else if discriminant.abs() < 1e-6 {
+                    x_values.push(-b / (2.0 * a));
+                } else if discriminant > 0.0 {
+                    x_values.push((-b + discriminant.sqrt()) / (2.0 * a));
+                    x_values.push((-b - discriminant.sqrt()) / (2.0 * a));
+                }
+
+                intersection_coordinates.extend(x_values.iter().map(|intersection_x| {
+                    (
+                        *intersection_x,
+                        eyeline_gradient * intersection_x + eyeline_y_intercept,
+                    )
+                }));
+            }
+
+            for intersection in intersection_coordinates {
+                let intersection_x = intersection.0;
+                let intersection_y = intersection.1;
+
+                let delta_x = intersection_x - creature_x;
+                let delta_y = intersection_y - creature_y;
+
+                let object_vector = Vec2::new(delta_x, delta_y);
+                let eyeline_vector = Vec2::new(global_eye_angle.cos(), global_eye_angle.sin());
+
+                if object_vector.dot(eyeline_vector) <= 0.0 {
+                    continue;
+                }
+
+                let distance = (delta_x.powi(2) + delta_y.powi(2)).sqrt();
+
+                if distance > seeing_distance {
+                    continue;
+                }
+
+                let new_eye_value = E.powf(-0.5 * distance);
+
+                let (eye_value, kind_value) =
+                    lines_of_sight.values_mut(&eye_angle.0, object.category);
+
+                if new_eye_value > *eye_value {
+                    *eye_value = new_eye_value
                 }
 
-                for eye_angle in EYE_ANGLES {
-                    let global_eye_angle =
-                        eye_angle.1 + transform.rotation.to_euler(EulerRot::XYZ).2;
-
-                    let eyeline_is_vertical =
-                        (global_eye_angle.abs() % PI - PI / 2.0).abs() < f32::EPSILON;
-
-                    let mut intersection_coordinates: Vec<(f32, f32)> = Vec::new();
-
-                    if eyeline_is_vertical {
-                        // The intersection of a vertical eyeline and the object can be re-arranged into a quadratic in the form ay^2 + by + c
-                        let a = 1.0;
-                        let b = -2.0 * object.y;
-                        let c = object.x.powi(2) + object.y.powi(2) + creature_x.powi(2)
-                            - 2.0 * object.x * creature_x
-                            - object.radius;
-
-                        let discriminant = b.powi(2) - 4.0 * a * c;
-
-                        let mut y_values: Vec<f32> = Vec::new();
-
-                        if discriminant < 0.0 {
-                            continue;
-                        } else if discriminant.abs() < 1e-6 {
-                            y_values.push(-b / (2.0 * a));
-                        } else if discriminant > 0.0 {
-                            y_values.push((-b + discriminant.sqrt()) / (2.0 * a));
-                            y_values.push((-b - discriminant.sqrt()) / (2.0 * a));
-                        }
-
-                        intersection_coordinates.extend(
-                            y_values
-                                .iter()
-                                .map(|intersection_y| (creature_x, *intersection_y)),
-                        );
-                    } else {
-                        let eyeline_gradient = global_eye_angle.tan();
-                        let eyeline_y_intercept =
-                            transform.translation.y - eyeline_gradient * transform.translation.x;
-
-                        // The intersection of the eyeline and the object can be re-arranged into a quadratic in the form ax^2 + bx + c
-                        let a = eyeline_gradient.powi(2) + 1.0;
-                        let b = 2.0 * eyeline_gradient * eyeline_y_intercept
-                            - 2.0 * eyeline_gradient * object.y
-                            - 2.0 * object.x;
-                        let c = object.x.powi(2) + object.y.powi(2) + eyeline_y_intercept.powi(2)
-                            - 2.0 * object.y * eyeline_y_intercept
-                            - object.radius;
-
-                        let discriminant = b.powi(2) - 4.0 * a * c;
-
-                        let mut x_values: Vec<f32> = Vec::new();
-
-                        if discriminant < 0.0 {
-                            continue;
-                        } else if discriminant.abs() < 1e-6 {
-                            x_values.push(-b / (2.0 * a));
-                        } else if discriminant > 0.0 {
-                            x_values.push((-b + discriminant.sqrt()) / (2.0 * a));
-                            x_values.push((-b - discriminant.sqrt()) / (2.0 * a));
-                        }
-
-                        intersection_coordinates.extend(x_values.iter().map(|intersection_x| {
-                            (
-                                *intersection_x,
-                                eyeline_gradient * intersection_x + eyeline_y_intercept,
-                            )
-                        }));
-                    }
-
-                    for intersection in intersection_coordinates {
-                        let intersection_x = intersection.0;
-                        let intersection_y = intersection.1;
-
-                        let delta_x = intersection_x - creature_x;
-                        let delta_y = intersection_y - creature_y;
-
-                        let object_vector = Vec2::new(delta_x, delta_y);
-                        let eyeline_vector =
-                            Vec2::new(global_eye_angle.cos(), global_eye_angle.sin());
-
-                        if object_vector.dot(eyeline_vector) <= 0.0 {
-                            continue;
-                        }
-
-                        let distance = (delta_x.powi(2) + delta_y.powi(2)).sqrt();
-
-                        if distance > SEEING_DISTANCE as f32 {
-                            continue;
-                        }
-
-                        let new_eye_value = E.powf(-0.5 * distance);
-
-                        let eye_value = match eye_angle.0 {
-                            EyeAngle::Left => match object.category {
-                                ObjectCategory::Creature => &mut lines_of_sight.left_creature,
-                                ObjectCategory::Food => &mut lines_of_sight.left_food,
-                            },
-                            EyeAngle::Middle => match object.category {
-                                ObjectCategory::Creature => &mut lines_of_sight.middle_creature,
-                                ObjectCategory::Food => &mut lines_of_sight.middle_food,
-                            },
-                            EyeAngle::Right => match object.category {
-                                ObjectCategory::Creature => &mut lines_of_sight.right_creature,
-                                ObjectCategory::Food => &mut lines_of_sight.right_food,
-                            },
-                        };
-
-                        if new_eye_value > *eye_value {
-                            *eye_value = new_eye_value
-                        }
-                    }
+                if let Some(kind_value) = kind_value
+                    && new_eye_value > *kind_value
+                {
+                    *kind_value = new_eye_value
                 }
             }
         }
     }
 
-    return lines_of_sight;
+    lines_of_sight
 }
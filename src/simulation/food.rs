@@ -1,49 +1,116 @@
-use std::time::Duration;
-
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, f64::consts::TAU, fmt::Display};
 
 use super::{
-    INITIAL_FOOD, SEEING_DISTANCE, WORLD_BOUNDS,
-    creature::Energy,
-    spatial_index::{ObjectCategory, SpatialIndex, get_cell_coordinates},
+    FoodConfig, FoodPatch, Seasons, SimulationConfig, SimulationRng, SimulationSet,
+    creature::{Energy, Lineage},
+    every,
+    snapshot::RestoredFromSnapshot,
+    spatial_index::{ObjectCategory, SpatialIndex},
 };
 use crate::model::creature::brain::Brain;
 
-#[derive(Component)]
-pub struct Food;
+/// A piece of food, and the energy which a creature would gain from eating it now.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Food {
+    pub kind: FoodKind,
+    /// This moves towards zero as the food decays.
+    pub nutrition: f32,
+}
+
+impl Food {
+    /// Returns a piece of food which has not yet started to decay.
+    pub fn fresh(kind: FoodKind, config: &FoodConfig) -> Self {
+        Self {
+            kind,
+            nutrition: config.kind(kind).energy,
+        }
+    }
+}
+
+/// The kinds of food, which creatures can tell apart by sight. Each has its own nutrition and rate of decay.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoodKind {
+    /// Grows anywhere in the world, unless configured otherwise.
+    #[default]
+    Plant,
+    /// Only grows in patches which are configured to grow it.
+    Fruit,
+    /// Is left behind by creatures when they die.
+    Carrion,
+    /// Only grows in patches which are configured to grow it, and costs energy to eat.
+    Toxic,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 4] = [
+        FoodKind::Plant,
+        FoodKind::Fruit,
+        FoodKind::Carrion,
+        FoodKind::Toxic,
+    ];
+}
+
+impl Display for FoodKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FoodKind::Plant => "plant",
+            FoodKind::Fruit => "fruit",
+            FoodKind::Carrion => "carrion",
+            FoodKind::Toxic => "toxic",
+        };
+
+        write!(f, "{name}")
+    }
+}
 
 #[derive(Bundle)]
 pub struct FoodBundle {
-    pub mesh: Mesh2d,
-    pub mesh_material: MeshMaterial2d<ColorMaterial>,
     pub transform: Transform,
-    pub visibility: Visibility,
     pub food: Food,
 }
 
 impl FoodBundle {
-    pub fn random(
-        materials: &mut ResMut<Assets<ColorMaterial>>,
-        meshes: &mut ResMut<Assets<Mesh>>,
-    ) -> Self {
-        let circle = meshes.add(Circle::new(0.5));
+    /// Returns the food at a random position anywhere in the world.
+    pub fn random<R: Rng + ?Sized>(food: Food, world_bounds: f32, generator: &mut R) -> Self {
+        FoodBundle {
+            transform: Transform {
+                translation: Vec3::new(
+                    generator.gen_range(-world_bounds..=world_bounds),
+                    generator.gen_range(-world_bounds..=world_bounds),
+                    -1.0,
+                ),
+                ..default()
+            },
+            food,
+        }
+    }
 
-        let mut generator = rand::thread_rng();
+    /// Returns a piece of food at a random position within a patch, but never beyond the bounds of the world.
+    pub fn in_patch<R: Rng + ?Sized>(
+        food: Food,
+        patch: &FoodPatch,
+        world_bounds: f32,
+        generator: &mut R,
+    ) -> Self {
+        // Taking the square root spreads the food evenly over the area of the patch, rather than bunching it at the
+        // centre.
+        let distance = patch.radius * generator.gen_range(0.0f32..=1.0).sqrt();
+        let angle = generator.gen_range(0.0..std::f32::consts::TAU);
 
         FoodBundle {
-            mesh: Mesh2d(circle),
-            mesh_material: MeshMaterial2d(materials.add(Color::linear_rgb(0.0, 1.0, 0.0))),
             transform: Transform {
                 translation: Vec3::new(
-                    generator.gen_range(-WORLD_BOUNDS..=WORLD_BOUNDS),
-                    generator.gen_range(-WORLD_BOUNDS..=WORLD_BOUNDS),
+                    (patch.x + distance * angle.cos()).clamp(-world_bounds, world_bounds),
+                    (patch.y + distance * angle.sin()).clamp(-world_bounds, world_bounds),
                     -1.0,
                 ),
                 ..default()
             },
-            visibility: Visibility::Visible,
-            food: Food,
+            food,
         }
     }
 }
@@ -52,83 +119,265 @@ pub struct FoodPlugin;
 
 impl Plugin for FoodPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, place_initial_food);
-        app.add_systems(FixedUpdate, check_consumption);
+        app.init_resource::<SimulationConfig>();
+        app.init_resource::<SimulationRng>();
+
+        let replacement_interval = app
+            .world()
+            .resource::<SimulationConfig>()
+            .food
+            .replacement_interval;
+
+        app.configure_sets(
+            Startup,
+            SimulationSet::Food.before(SimulationSet::Creatures),
+        );
+        app.configure_sets(
+            FixedUpdate,
+            SimulationSet::Food.before(SimulationSet::Creatures),
+        );
 
         app.add_systems(
-            Update,
-            replace_food.run_if(on_timer(Duration::from_secs_f64(0.1))),
+            Startup,
+            place_initial_food
+                .run_if(not(resource_exists::<RestoredFromSnapshot>))
+                .in_set(SimulationSet::Food),
+        );
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                check_consumption,
+                decay_food,
+                grow_food.run_if(every(replacement_interval)),
+            )
+                .chain()
+                .in_set(SimulationSet::Food),
         );
     }
 }
 
+/// Spreads the initial food between the world and its patches, in proportion to their growth rates.
 fn place_initial_food(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut generator: ResMut<SimulationRng>,
+    config: Res<SimulationConfig>,
+) {
+    let food = &config.food;
+
+    let total_growth_rate = food.growth_rate
+        + food
+            .patches
+            .iter()
+            .map(|patch| patch.growth_rate)
+            .sum::<f64>();
+
+    for _ in 0..food.initial {
+        // Without any patches, or if nothing grows, all of the food is scattered over the world.
+        if food.patches.is_empty() || total_growth_rate <= 0.0 {
+            commands.spawn(FoodBundle::random(
+                Food::fresh(food.kind, food),
+                config.world.bounds,
+                &mut *generator,
+            ));
+            continue;
+        }
+
+        // The choice falls in the world's share first, then in each patch's share in turn.
+        let mut choice = generator.gen_range(0.0..total_growth_rate);
+
+        let patch = if choice < food.growth_rate {
+            None
+        } else {
+            choice -= food.growth_rate;
+
+            food.patches.iter().find(|patch| {
+                choice -= patch.growth_rate;
+                choice < 0.0
+            })
+        };
+
+        let bundle = match patch {
+            Some(patch) => FoodBundle::in_patch(
+                Food::fresh(patch.kind, food),
+                patch,
+                config.world.bounds,
+                &mut *generator,
+            ),
+            None => FoodBundle::random(
+                Food::fresh(food.kind, food),
+                config.world.bounds,
+                &mut *generator,
+            ),
+        };
+
+        commands.spawn(bundle);
+    }
+}
+
+/// Grows new food over the world and in each of its patches, more slowly the closer each is to its capacity.
+fn grow_food(
+    mut commands: Commands,
+    query: Query<&Transform, With<Food>>,
+    mut generator: ResMut<SimulationRng>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
 ) {
-    for _ in 0..INITIAL_FOOD {
-        commands.spawn(FoodBundle::random(&mut materials, &mut meshes));
+    let food = &config.food;
+
+    let duration = food.replacement_interval
+        * season_multiplier(food.seasons.as_ref(), time.elapsed_secs_f64());
+
+    let mut patch_counts = vec![0; food.patches.len()];
+    let mut elsewhere_count = 0;
+
+    for transform in &query {
+        match patch_containing(food, transform.translation.truncate()) {
+            Some(index) => patch_counts[index] += 1,
+            None => elsewhere_count += 1,
+        }
+    }
+
+    let expected = expected_growth(food.growth_rate, food.capacity, elsewhere_count, duration);
+
+    for _ in 0..pieces(expected, &mut *generator) {
+        commands.spawn(FoodBundle::random(
+            Food::fresh(food.kind, food),
+            config.world.bounds,
+            &mut *generator,
+        ));
+    }
+
+    for (patch, count) in food.patches.iter().zip(patch_counts) {
+        let expected = expected_growth(patch.growth_rate, Some(patch.capacity), count, duration);
+
+        for _ in 0..pieces(expected, &mut *generator) {
+            commands.spawn(FoodBundle::in_patch(
+                Food::fresh(patch.kind, food),
+                patch,
+                config.world.bounds,
+                &mut *generator,
+            ));
+        }
     }
 }
 
-fn replace_food(
+/// Reduces the nutrition of every piece of food which decays, and removes those with none left.
+fn decay_food(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut query: Query<(Entity, &mut Food)>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
 ) {
-    commands.spawn(FoodBundle::random(&mut materials, &mut meshes));
+    for (entity, mut food) in &mut query {
+        let decay = config.food.kind(food.kind).decay_rate * time.delta_secs();
+
+        if decay == 0.0 {
+            continue;
+        }
+
+        // Toxic food decays towards being harmless, just as other food decays towards being worthless.
+        let nutrition = food.nutrition.abs() - decay;
+
+        if nutrition <= 0.0 {
+            commands.entity(entity).despawn();
+        } else {
+            food.nutrition = nutrition.copysign(food.nutrition);
+        }
+    }
+}
+
+/// Returns how much faster than usual food grows at the given time of year.
+fn season_multiplier(seasons: Option<&Seasons>, elapsed: f64) -> f64 {
+    match seasons {
+        Some(seasons) => 1.0 + seasons.amplitude * (TAU * elapsed / seasons.period).sin(),
+        None => 1.0,
+    }
+}
+
+/// Returns the index of the first patch which contains the given position, if any does.
+fn patch_containing(food: &FoodConfig, position: Vec2) -> Option<usize> {
+    food.patches.iter().position(|patch| {
+        position.distance_squared(Vec2::new(patch.x, patch.y)) <= patch.radius.powi(2)
+    })
+}
+
+/// Returns the number of pieces of food which are expected to grow in a region over the given duration.
+///
+/// Growth is logistic: it slows as the food in the region approaches its capacity, and stops once it is reached. Fast
+/// growth never overshoots the capacity.
+fn expected_growth(growth_rate: f64, capacity: Option<u32>, count: usize, duration: f64) -> f64 {
+    let Some(capacity) = capacity else {
+        return growth_rate * duration;
+    };
+
+    let room = capacity.saturating_sub(count as u32) as f64;
+
+    if room == 0.0 {
+        return 0.0;
+    }
+
+    (growth_rate * duration * room / capacity as f64).min(room)
+}
+
+/// Rounds an expected number of pieces up or down at random, so that growth slower than one piece at a time still
+/// happens on average.
+fn pieces<R: Rng + ?Sized>(expected: f64, generator: &mut R) -> u32 {
+    let whole = expected.floor();
+    let fraction = expected - whole;
+
+    // Nothing is drawn from the generator when a whole number of pieces is expected.
+    let extra = fraction > 0.0 && generator.gen_bool(fraction);
+
+    whole as u32 + extra as u32
 }
 
 fn check_consumption(
-    mut creature_query: Query<(&Transform, Entity, &mut Energy), With<Brain>>,
+    mut creature_query: Query<(&Transform, &mut Energy, &Lineage), With<Brain>>,
+    food_query: Query<&Food>,
     mut commands: Commands,
     spatial_index: Res<SpatialIndex>,
+    config: Res<SimulationConfig>,
 ) {
-    let spatial_index = &spatial_index.index;
+    let mut creatures: Vec<_> = creature_query.iter_mut().collect();
+
+    // Query order is not deterministic, and the first creature to reach a piece of food is the one which eats it.
+    creatures.sort_by_key(|(.., lineage)| lineage.id);
 
-    for mut creature in &mut creature_query {
-        let transform = creature.0;
+    // Despawning is deferred, so eaten food stays in the spatial index until the end of the tick.
+    let mut eaten: HashSet<Entity> = HashSet::new();
 
+    for (transform, mut energy, _) in creatures {
         let (creature_x, creature_y) = (transform.translation.x, transform.translation.y);
 
-        let (cell_x, cell_y) = get_cell_coordinates(creature_x, creature_y);
-
-        // This forms a 3 by 3 grid, centred at the current cell.
-        let cells_to_search = [
-            (cell_x, cell_y),
-            (cell_x - SEEING_DISTANCE * 2, cell_y),
-            (cell_x + SEEING_DISTANCE * 2, cell_y),
-            (cell_x, cell_y - SEEING_DISTANCE * 2),
-            (cell_x, cell_y + SEEING_DISTANCE * 2),
-            (cell_x - SEEING_DISTANCE * 2, cell_y - SEEING_DISTANCE * 2),
-            (cell_x + SEEING_DISTANCE * 2, cell_y - SEEING_DISTANCE * 2),
-            (cell_x - SEEING_DISTANCE * 2, cell_y + SEEING_DISTANCE * 2),
-            (cell_x + SEEING_DISTANCE * 2, cell_y + SEEING_DISTANCE * 2),
-        ];
-
-        for cell in cells_to_search {
-            let objects = spatial_index.get(&cell);
-
-            if let Some(objects) = objects {
-                let food = objects
-                    .iter()
-                    .filter(|object| object.category == ObjectCategory::Food);
-
-                for food_piece in food {
-                    if let Some(mut entity) = commands.get_entity(food_piece.entity) {
-                        let delta_x = creature_x - food_piece.x;
-                        let delta_y = creature_y - food_piece.y;
-
-                        if delta_x.powi(2) + delta_y.powi(2) > 1.0 {
-                            continue;
-                        }
-
-                        creature.2.value += 1000.0;
-
-                        entity.despawn();
-                    }
+        let food = spatial_index
+            .neighbourhood(creature_x, creature_y)
+            .filter(|object| matches!(object.category, ObjectCategory::Food(_)));
+
+        for food_piece in food {
+            if eaten.contains(&food_piece.entity) {
+                continue;
+            }
+
+            if let Some(mut entity) = commands.get_entity(food_piece.entity) {
+                let (food_x, food_y) =
+                    spatial_index.nearest_image(creature_x, creature_y, food_piece.x, food_piece.y);
+
+                let delta_x = creature_x - food_x;
+                let delta_y = creature_y - food_y;
+
+                if delta_x.powi(2) + delta_y.powi(2) > config.food.consumption_distance.powi(2) {
+                    continue;
                 }
+
+                let Ok(food) = food_query.get(food_piece.entity) else {
+                    continue;
+                };
+
+                energy.value += food.nutrition;
+
+                eaten.insert(food_piece.entity);
+                entity.despawn();
             }
         }
     }
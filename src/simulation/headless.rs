@@ -0,0 +1,28 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use std::time::Duration;
+
+use super::SimulationConfig;
+
+/// Runs the simulation without a window or renderer.
+///
+/// Rather than following the wall clock, time is advanced by exactly one fixed timestep per update, and updates are run
+/// back to back. This means that the simulation runs as fast as the CPU allows.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<SimulationConfig>();
+
+        let fixed_update_frequency = app
+            .world()
+            .resource::<SimulationConfig>()
+            .time
+            .fixed_update_frequency;
+
+        let timestep = Duration::from_secs_f64(1.0 / fixed_update_frequency);
+
+        app.insert_resource(Time::<Fixed>::from_duration(timestep));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    }
+}
@@ -0,0 +1,358 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    creature::{CreatureDied, CreatureId, Lineage},
+    snapshot::RestoredFromSnapshot,
+};
+
+/// Streams every birth and death to a lineage log, in JSON Lines format, from which the ancestry of the population can
+/// be rebuilt with [LineageTree].
+///
+/// A fresh run replaces any existing log. A run which was restored from a snapshot appends to the log instead, after a
+/// marker which tells [LineageTree] to discard anything recorded after the snapshot was taken.
+pub struct LineagePlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for LineagePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LineageLog {
+            path: self.path.clone(),
+            writer: None,
+            resumed: None,
+        });
+
+        app.add_systems(Startup, open_lineage_log);
+        app.add_systems(FixedLast, record_lineage);
+    }
+}
+
+/// A single line of the lineage log.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum LineageRecord {
+    Birth {
+        id: CreatureId,
+        parent: Option<CreatureId>,
+        #[serde(default)]
+        mate: Option<CreatureId>,
+        generation: u32,
+        time: f64,
+    },
+    Death {
+        id: CreatureId,
+        time: f64,
+    },
+    /// The simulation was restored from a snapshot taken at this time.
+    Resume {
+        time: f64,
+    },
+}
+
+#[derive(Resource)]
+struct LineageLog {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    /// The time at which the run was restored from a snapshot, if it was.
+    resumed: Option<f64>,
+}
+
+impl LineageLog {
+    fn write(&mut self, record: &LineageRecord) -> Result<()> {
+        if let Some(writer) = &mut self.writer {
+            serde_json::to_writer(&mut *writer, record)?;
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn open_lineage_log(
+    mut log: ResMut<LineageLog>,
+    restored: Option<Res<RestoredFromSnapshot>>,
+    time: Res<Time<Fixed>>,
+) {
+    let file = if restored.is_some() {
+        OpenOptions::new().create(true).append(true).open(&log.path)
+    } else {
+        File::create(&log.path)
+    };
+
+    match file {
+        Ok(file) => log.writer = Some(BufWriter::new(file)),
+        Err(error) => {
+            error!(
+                "Could not open the lineage log {}: {error}",
+                log.path.display()
+            );
+            return;
+        }
+    }
+
+    if restored.is_some() {
+        log.resumed = Some(time.elapsed_secs_f64());
+
+        let record = LineageRecord::Resume {
+            time: time.elapsed_secs_f64(),
+        };
+
+        if let Err(error) = log.write(&record) {
+            error!("Could not write to the lineage log: {error:?}");
+        }
+    }
+}
+
+fn record_lineage(
+    births: Query<&Lineage, Added<Lineage>>,
+    mut deaths: EventReader<CreatureDied>,
+    time: Res<Time<Fixed>>,
+    mut log: ResMut<LineageLog>,
+) {
+    // Every creature restored from a snapshot counts as added, but its birth was logged before the snapshot was taken.
+    let resumed = log.resumed.unwrap_or(f64::NEG_INFINITY);
+
+    let mut records: Vec<LineageRecord> = births
+        .iter()
+        .filter(|lineage| lineage.birth_time > resumed)
+        .map(|lineage| LineageRecord::Birth {
+            id: lineage.id,
+            parent: lineage.parent,
+            mate: lineage.mate,
+            generation: lineage.generation,
+            time: lineage.birth_time,
+        })
+        .collect();
+
+    // Query order is not the order of birth.
+    records.sort_by_key(|record| match record {
+        LineageRecord::Birth { id, .. } => *id,
+        _ => unreachable!(),
+    });
+
+    records.extend(deaths.read().map(|death| LineageRecord::Death {
+        id: death.id,
+        time: time.elapsed_secs_f64(),
+    }));
+
+    if records.is_empty() {
+        return;
+    }
+
+    // The log is flushed every tick in which something happened, so that nothing older than the latest checkpoint can
+    // be lost if the run is killed.
+    let result = records
+        .iter()
+        .try_for_each(|record| log.write(record))
+        .and_then(|()| match &mut log.writer {
+            Some(writer) => Ok(writer.flush()?),
+            None => Ok(()),
+        });
+
+    if let Err(error) = result {
+        error!("Could not write to the lineage log: {error:?}");
+    }
+}
+
+/// A creature in a [LineageTree].
+#[derive(Clone, Debug)]
+pub struct LineageNode {
+    /// The parent which the creature descends from in the tree.
+    pub parent: Option<CreatureId>,
+    /// The second parent, if the creature was born through sexual reproduction. This is not part of the tree.
+    pub mate: Option<CreatureId>,
+    pub generation: u32,
+    pub birth_time: f64,
+    /// The time at which the creature died, or nothing if it was still alive at the end of the log.
+    pub death_time: Option<f64>,
+}
+
+/// The ancestry of every creature recorded in a lineage log.
+pub struct LineageTree {
+    nodes: BTreeMap<CreatureId, LineageNode>,
+}
+
+impl LineageTree {
+    /// Rebuilds the tree from a lineage log written by [LineagePlugin].
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the lineage log {}.", path.display()))?;
+
+        let mut nodes: BTreeMap<CreatureId, LineageNode> = BTreeMap::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: LineageRecord = serde_json::from_str(line).with_context(|| {
+                format!(
+                    "Line {} of the lineage log {} is invalid.",
+                    index + 1,
+                    path.display()
+                )
+            })?;
+
+            match record {
+                LineageRecord::Birth {
+                    id,
+                    parent,
+                    mate,
+                    generation,
+                    time,
+                } => {
+                    nodes.insert(
+                        id,
+                        LineageNode {
+                            parent,
+                            mate,
+                            generation,
+                            birth_time: time,
+                            death_time: None,
+                        },
+                    );
+                }
+                LineageRecord::Death { id, time } => {
+                    if let Some(node) = nodes.get_mut(&id) {
+                        node.death_time = Some(time);
+                    }
+                }
+                LineageRecord::Resume { time } => {
+                    // Anything after the snapshot belongs to a timeline which has been abandoned.
+                    nodes.retain(|_, node| node.birth_time <= time);
+
+                    for node in nodes.values_mut() {
+                        if node.death_time.is_some_and(|death_time| death_time > time) {
+                            node.death_time = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Returns every creature in the tree.
+    pub fn nodes(&self) -> &BTreeMap<CreatureId, LineageNode> {
+        &self.nodes
+    }
+
+    /// Removes every creature which has no surviving descendants, and did not survive itself.
+    ///
+    /// What remains is the ancestry of the survivors, in which their common ancestors can be found.
+    pub fn survivors_only(mut self) -> Self {
+        let mut kept = BTreeSet::new();
+
+        for (&id, node) in &self.nodes {
+            if node.death_time.is_some() {
+                continue;
+            }
+
+            let mut current = Some(id);
+
+            while let Some(id) = current {
+                if !kept.insert(id) {
+                    break;
+                }
+
+                current = self.nodes.get(&id).and_then(|node| node.parent);
+            }
+        }
+
+        self.nodes.retain(|id, _| kept.contains(id));
+
+        self
+    }
+
+    /// Writes the tree in Newick format, labelling each creature by its ID.
+    ///
+    /// Branch lengths are the time between the births of a parent and its child. Generation zero is joined under an
+    /// unlabelled root.
+    pub fn to_newick(&self) -> String {
+        let mut children: BTreeMap<CreatureId, Vec<CreatureId>> = BTreeMap::new();
+        let mut roots = Vec::new();
+
+        for (&id, node) in &self.nodes {
+            match node.parent.filter(|parent| self.nodes.contains_key(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(id),
+                None => roots.push(id),
+            }
+        }
+
+        let mut newick = String::from("(");
+
+        for (index, &root) in roots.iter().enumerate() {
+            if index > 0 {
+                newick.push(',');
+            }
+
+            self.write_newick_subtree(&mut newick, root, &children);
+        }
+
+        newick.push_str(");");
+
+        newick
+    }
+
+    /// Writes a subtree without recursion, as lineages can be far deeper than the stack allows.
+    fn write_newick_subtree(
+        &self,
+        newick: &mut String,
+        root: CreatureId,
+        children: &BTreeMap<CreatureId, Vec<CreatureId>>,
+    ) {
+        // Each entry is a creature, and how many of its children have been written so far.
+        let mut stack = vec![(root, 0)];
+
+        while let Some((id, written)) = stack.last_mut() {
+            let id = *id;
+            let own_children = children.get(&id).map(Vec::as_slice).unwrap_or_default();
+
+            if *written < own_children.len() {
+                newick.push(if *written == 0 { '(' } else { ',' });
+
+                let child = own_children[*written];
+                *written += 1;
+                stack.push((child, 0));
+                continue;
+            }
+
+            if !own_children.is_empty() {
+                newick.push(')');
+            }
+
+            let node = &self.nodes[&id];
+            let parent_birth_time = node
+                .parent
+                .and_then(|parent| self.nodes.get(&parent))
+                .map_or(0.0, |parent| parent.birth_time);
+
+            write!(newick, "{id}:{}", node.birth_time - parent_birth_time).unwrap();
+
+            stack.pop();
+        }
+    }
+
+    /// Writes the tree as CSV, with a row for each parent and child pair.
+    pub fn to_edge_list(&self) -> String {
+        let mut edges = String::from("parent,child\n");
+
+        for (id, node) in &self.nodes {
+            if let Some(parent) = node.parent.filter(|parent| self.nodes.contains_key(parent)) {
+                writeln!(edges, "{parent},{id}").unwrap();
+            }
+        }
+
+        edges
+    }
+}
@@ -1,37 +1,54 @@
 //! Contains code related to running the simulation.
 
+mod appearance;
+mod checkpoint;
+mod config;
 mod creature;
 mod food;
+mod headless;
+mod lineage;
+pub mod neurons;
+mod physics;
+mod rng;
 mod setup;
+mod snapshot;
 mod spatial_index;
+mod species;
+mod statistics;
+mod tick_limit;
+mod topology;
 
-use bevy::{math::Vec2, prelude::Component};
+use bevy::{
+    ecs::schedule::SystemSet,
+    math::Vec2,
+    prelude::{Component, Fixed, Res, Time},
+};
+use std::time::Duration;
 
-pub use creature::CreaturePlugin;
-pub use food::FoodPlugin;
+pub use appearance::{AppearancePlugin, ColourMode};
+pub use checkpoint::{
+    CheckpointPlugin, LatestCheckpoint, NoValidCheckpoint, SkippedCheckpoint, latest_checkpoint,
+};
+pub use config::{
+    CreatureConfig, EnergyConfig, FoodConfig, FoodKindConfig, FoodPatch, GenomeSanitisation,
+    InvalidConfig, PhysicsConfig, ReproductionConfig, ReproductionMode, RepulsiveBorder, Seasons,
+    SimulationConfig, SpeciationConfig, SpeciationMethod, TimeConfig, Topology, WallCollision,
+    Walls, WorldConfig,
+};
+pub use creature::{Age, CreatureBorn, CreatureDied, CreatureId, CreaturePlugin, Energy, Lineage};
+pub use food::{Food, FoodKind, FoodPlugin};
+pub use headless::HeadlessPlugin;
+pub use lineage::{LineageNode, LineagePlugin, LineageTree};
+pub use rng::SimulationRng;
 pub use setup::SetupPlugin;
+pub use snapshot::{SNAPSHOT_VERSION, Snapshot, SnapshotOnExitPlugin};
 pub use spatial_index::SpatialIndexPlugin;
-
-/// The maximum number of internal neurons a creature's brain can contain.
-pub const MAX_INTERNAL_NEURONS: u8 = 10;
-/// The maximum number of genes that may exist in a creature's genome.
-pub const GENOME_LENGTH: usize = 20;
-/// The number of creatures in the first generation.
-pub const GENERATION_ZERO_SIZE: u32 = 1000;
-/// The frequency, measured in Hz, at which the physics system should be updated.
-pub const FIXED_UPDATE_FREQUENCY: f64 = 1000.0;
-/// The frequency, measured in Hz, at which the creatures should recalculate their brain state;
-pub const BRAIN_UPDATE_FREQUENCY: f64 = 10.0;
-/// The initial energy a creature should have.
-pub const INITIAL_ENERGY: f32 = 1000.0;
-/// The distance that a creature is able to see
-pub const SEEING_DISTANCE: i32 = 10;
-/// The initial quantity of food to spawn.
-pub const INITIAL_FOOD: i32 = 10000;
-/// On average, 1 in every MUTATION_RATE bits will be flipped
-pub const MUTATION_RATE: f32 = 1.0 / 1000.0;
-/// The bounds of the world.
-pub const WORLD_BOUNDS: f32 = 1000.0;
+pub use species::{
+    SpeciationPlugin, Species, SpeciesBorn, SpeciesExtinct, SpeciesId, SpeciesRecord,
+    SpeciesRegistry,
+};
+pub use statistics::{StatisticsFormat, StatisticsPlugin};
+pub use tick_limit::TickLimitPlugin;
 
 #[derive(Component)]
 pub struct Velocity {
@@ -42,3 +59,52 @@ pub struct Velocity {
 pub struct AngularVelocity {
     pub value: f32,
 }
+
+/// The force which a creature is exerting on itself, held until its brain is next updated.
+#[derive(Component, Default)]
+pub struct Force {
+    pub value: Vec2,
+}
+
+/// The torque which a creature is exerting on itself, held until its brain is next updated.
+#[derive(Component, Default)]
+pub struct Torque {
+    pub value: f32,
+}
+
+/// Whether a creature has collided with another since its brain was last updated.
+#[derive(Component, Default)]
+pub struct Contact {
+    pub value: bool,
+}
+
+/// Groups the simulation's systems, so that they can be run in a fixed order.
+///
+/// Systems which share the [SimulationRng], or touch the same components, must never run in an arbitrary order,
+/// otherwise two runs from the same seed would diverge.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    /// Systems which spawn and consume food.
+    Food,
+    /// Systems which spawn, update and kill creatures.
+    Creatures,
+}
+
+/// A run condition for the fixed schedules, which is true once every `period` seconds of simulated time.
+///
+/// Unlike [bevy::time::common_conditions::on_timer], this only depends on the elapsed fixed time, so a simulation
+/// restored from a snapshot keeps exactly the same rhythm.
+pub fn every(period: f64) -> impl FnMut(Res<Time<Fixed>>) -> bool + Clone {
+    let period = Duration::from_secs_f64(period);
+
+    move |time: Res<Time<Fixed>>| period_elapsed(&time, period)
+}
+
+/// Whether the latest fixed timestep crossed a multiple of `period`.
+fn period_elapsed(time: &Time<Fixed>, period: Duration) -> bool {
+    let period = period.as_nanos().max(1);
+    let elapsed = time.elapsed().as_nanos();
+    let previous = elapsed - time.delta().as_nanos();
+
+    elapsed / period > previous / period
+}
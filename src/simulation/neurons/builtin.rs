@@ -0,0 +1,280 @@
+use bevy::prelude::*;
+
+use super::{Actuator, ActuatorPlugin, Sensor, SensorPlugin};
+use crate::simulation::{
+    AngularVelocity, Contact, Force, SimulationConfig, Torque, Velocity,
+    creature::{Age, Energy, vision::LinesOfSight},
+};
+
+/// Registers the senses and actions which every creature has.
+///
+/// The order of registration decides which neuron each gene id chooses, so it must never change. New senses and
+/// actions must be added at the end.
+pub struct BuiltinNeuronsPlugin;
+
+impl Plugin for BuiltinNeuronsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            SensorPlugin::<AgeSensor>::default(),
+            SensorPlugin::<SpeedSensor>::default(),
+            SensorPlugin::<AngularVelocitySensor>::default(),
+            SensorPlugin::<StoredEnergySensor>::default(),
+            SensorPlugin::<LeftCreatureSensor>::default(),
+            SensorPlugin::<LeftFoodSensor>::default(),
+            SensorPlugin::<MiddleCreatureSensor>::default(),
+            SensorPlugin::<MiddleFoodSensor>::default(),
+            SensorPlugin::<RightCreatureSensor>::default(),
+            SensorPlugin::<RightFoodSensor>::default(),
+            ActuatorPlugin::<AccelerationActuator>::default(),
+            ActuatorPlugin::<AngularAccelerationActuator>::default(),
+            SensorPlugin::<CollisionSensor>::default(),
+        ))
+        .add_plugins((
+            SensorPlugin::<LeftPlantSensor>::default(),
+            SensorPlugin::<LeftFruitSensor>::default(),
+            SensorPlugin::<LeftCarrionSensor>::default(),
+            SensorPlugin::<LeftToxicSensor>::default(),
+            SensorPlugin::<MiddlePlantSensor>::default(),
+            SensorPlugin::<MiddleFruitSensor>::default(),
+            SensorPlugin::<MiddleCarrionSensor>::default(),
+            SensorPlugin::<MiddleToxicSensor>::default(),
+            SensorPlugin::<RightPlantSensor>::default(),
+            SensorPlugin::<RightFruitSensor>::default(),
+            SensorPlugin::<RightCarrionSensor>::default(),
+            SensorPlugin::<RightToxicSensor>::default(),
+        ));
+    }
+}
+
+/// Senses how long the creature has been alive, in seconds.
+pub struct AgeSensor;
+
+impl Sensor for AgeSensor {
+    const NAME: &'static str = "Age";
+
+    type Data = &'static Age;
+    type Param = ();
+
+    fn sense(age: &Age, _: &()) -> f32 {
+        age.value
+    }
+}
+
+/// Senses how fast the creature is moving.
+pub struct SpeedSensor;
+
+impl Sensor for SpeedSensor {
+    const NAME: &'static str = "Speed";
+
+    type Data = &'static Velocity;
+    type Param = ();
+
+    fn sense(velocity: &Velocity, _: &()) -> f32 {
+        velocity.value.length()
+    }
+}
+
+/// Senses how fast, and in which direction, the creature is turning.
+pub struct AngularVelocitySensor;
+
+impl Sensor for AngularVelocitySensor {
+    const NAME: &'static str = "AngularVelocity";
+
+    type Data = &'static AngularVelocity;
+    type Param = ();
+
+    fn sense(angular_velocity: &AngularVelocity, _: &()) -> f32 {
+        angular_velocity.value
+    }
+}
+
+/// Senses how much energy the creature has.
+pub struct StoredEnergySensor;
+
+impl Sensor for StoredEnergySensor {
+    const NAME: &'static str = "StoredEnergy";
+
+    type Data = &'static Energy;
+    type Param = ();
+
+    fn sense(energy: &Energy, _: &()) -> f32 {
+        energy.value
+    }
+}
+
+/// Defines a sensor which reads one of the creature's [LinesOfSight].
+macro_rules! line_of_sight_sensor {
+    ($sensor:ident, $name:literal, $field:ident, $description:literal) => {
+        #[doc = $description]
+        pub struct $sensor;
+
+        impl Sensor for $sensor {
+            const NAME: &'static str = $name;
+
+            type Data = &'static LinesOfSight;
+            type Param = ();
+
+            fn sense(lines_of_sight: &LinesOfSight, _: &()) -> f32 {
+                lines_of_sight.$field
+            }
+        }
+    };
+}
+
+line_of_sight_sensor!(
+    LeftCreatureSensor,
+    "LeftCreature",
+    left_creature,
+    "Senses how close the nearest creature along the left eyeline is."
+);
+line_of_sight_sensor!(
+    LeftFoodSensor,
+    "LeftFood",
+    left_food,
+    "Senses how close the nearest food along the left eyeline is."
+);
+line_of_sight_sensor!(
+    MiddleCreatureSensor,
+    "MiddleCreature",
+    middle_creature,
+    "Senses how close the nearest creature straight ahead is."
+);
+line_of_sight_sensor!(
+    MiddleFoodSensor,
+    "MiddleFood",
+    middle_food,
+    "Senses how close the nearest food straight ahead is."
+);
+line_of_sight_sensor!(
+    RightCreatureSensor,
+    "RightCreature",
+    right_creature,
+    "Senses how close the nearest creature along the right eyeline is."
+);
+line_of_sight_sensor!(
+    RightFoodSensor,
+    "RightFood",
+    right_food,
+    "Senses how close the nearest food along the right eyeline is."
+);
+
+line_of_sight_sensor!(
+    LeftPlantSensor,
+    "LeftPlant",
+    left_plant,
+    "Senses how close the nearest plant along the left eyeline is."
+);
+line_of_sight_sensor!(
+    LeftFruitSensor,
+    "LeftFruit",
+    left_fruit,
+    "Senses how close the nearest fruit along the left eyeline is."
+);
+line_of_sight_sensor!(
+    LeftCarrionSensor,
+    "LeftCarrion",
+    left_carrion,
+    "Senses how close the nearest carrion along the left eyeline is."
+);
+line_of_sight_sensor!(
+    LeftToxicSensor,
+    "LeftToxic",
+    left_toxic,
+    "Senses how close the nearest toxic food along the left eyeline is."
+);
+line_of_sight_sensor!(
+    MiddlePlantSensor,
+    "MiddlePlant",
+    middle_plant,
+    "Senses how close the nearest plant straight ahead is."
+);
+line_of_sight_sensor!(
+    MiddleFruitSensor,
+    "MiddleFruit",
+    middle_fruit,
+    "Senses how close the nearest fruit straight ahead is."
+);
+line_of_sight_sensor!(
+    MiddleCarrionSensor,
+    "MiddleCarrion",
+    middle_carrion,
+    "Senses how close the nearest carrion straight ahead is."
+);
+line_of_sight_sensor!(
+    MiddleToxicSensor,
+    "MiddleToxic",
+    middle_toxic,
+    "Senses how close the nearest toxic food straight ahead is."
+);
+line_of_sight_sensor!(
+    RightPlantSensor,
+    "RightPlant",
+    right_plant,
+    "Senses how close the nearest plant along the right eyeline is."
+);
+line_of_sight_sensor!(
+    RightFruitSensor,
+    "RightFruit",
+    right_fruit,
+    "Senses how close the nearest fruit along the right eyeline is."
+);
+line_of_sight_sensor!(
+    RightCarrionSensor,
+    "RightCarrion",
+    right_carrion,
+    "Senses how close the nearest carrion along the right eyeline is."
+);
+line_of_sight_sensor!(
+    RightToxicSensor,
+    "RightToxic",
+    right_toxic,
+    "Senses how close the nearest toxic food along the right eyeline is."
+);
+
+/// Senses whether the creature has collided with another since its brain was last updated.
+pub struct CollisionSensor;
+
+impl Sensor for CollisionSensor {
+    const NAME: &'static str = "Collision";
+
+    type Data = &'static Contact;
+    type Param = ();
+
+    fn sense(contact: &Contact, _: &()) -> f32 {
+        if contact.value { 1.0 } else { 0.0 }
+    }
+}
+
+/// Pushes the creature in the direction it is facing, until its brain is next updated.
+pub struct AccelerationActuator;
+
+impl Actuator for AccelerationActuator {
+    const NAME: &'static str = "Acceleration";
+
+    type Data = (&'static Transform, &'static mut Force);
+    type Param = Res<'static, SimulationConfig>;
+
+    fn act(
+        activation: f32,
+        (transform, mut force): (&Transform, Mut<Force>),
+        config: &mut Res<SimulationConfig>,
+    ) {
+        let angle = transform.rotation.to_euler(EulerRot::XYZ).2;
+
+        force.value += Vec2::from_angle(angle) * activation * config.physics.thrust;
+    }
+}
+
+/// Turns the creature, until its brain is next updated.
+pub struct AngularAccelerationActuator;
+
+impl Actuator for AngularAccelerationActuator {
+    const NAME: &'static str = "AngularAcceleration";
+
+    type Data = &'static mut Torque;
+    type Param = Res<'static, SimulationConfig>;
+
+    fn act(activation: f32, mut torque: Mut<Torque>, config: &mut Res<SimulationConfig>) {
+        torque.value += activation * config.physics.torque;
+    }
+}
@@ -0,0 +1,210 @@
+//! Connects the sensory inputs and action outputs of brains to the simulation.
+//!
+//! Each kind of input is a [Sensor], and each kind of output is an [Actuator]. They are registered by adding a
+//! [SensorPlugin] or an [ActuatorPlugin] to the app, so new ones can be added without touching the brain. The built-in
+//! senses and actions are registered by the [crate::simulation::CreaturePlugin], before any others.
+
+mod builtin;
+
+use bevy::{
+    ecs::{
+        query::{QueryData, QueryItem, ReadOnlyQueryData},
+        system::{ReadOnlySystemParam, StaticSystemParam, SystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+use std::marker::PhantomData;
+
+use crate::model::creature::brain::{
+    ActionOutput, ActionOutputs, NeuronRegistry, SensoryInput, SensoryInputs,
+};
+pub use builtin::{
+    AccelerationActuator, AgeSensor, AngularAccelerationActuator, AngularVelocitySensor,
+    BuiltinNeuronsPlugin, CollisionSensor, LeftCarrionSensor, LeftCreatureSensor, LeftFoodSensor,
+    LeftFruitSensor, LeftPlantSensor, LeftToxicSensor, MiddleCarrionSensor, MiddleCreatureSensor,
+    MiddleFoodSensor, MiddleFruitSensor, MiddlePlantSensor, MiddleToxicSensor, RightCarrionSensor,
+    RightCreatureSensor, RightFoodSensor, RightFruitSensor, RightPlantSensor, RightToxicSensor,
+    SpeedSensor, StoredEnergySensor,
+};
+
+/// The stages of a brain update, which run in this order whenever brains are updated.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BrainSet {
+    /// Perceptions which several sensors share, such as vision, are computed.
+    Perceive,
+    /// Every [Sensor] writes its input.
+    Sense,
+    /// Every brain is evaluated.
+    Think,
+    /// Every [Actuator] applies its output, in the order in which they were registered.
+    Act,
+}
+
+/// A kind of sensory input, which reads a single value about each creature.
+pub trait Sensor: Send + Sync + 'static {
+    /// The name of the input, as shown when inspecting brains.
+    const NAME: &'static str;
+
+    /// The components of the creature which the sensor reads.
+    type Data: ReadOnlyQueryData;
+
+    /// Anything else the sensor reads, such as resources.
+    type Param: ReadOnlySystemParam;
+
+    /// Returns the value of the input for a single creature.
+    fn sense(creature: QueryItem<Self::Data>, param: &SystemParamItem<Self::Param>) -> f32;
+}
+
+/// A kind of action output, which affects each creature according to the activation of its action neuron.
+pub trait Actuator: Send + Sync + 'static {
+    /// The name of the output, as shown when inspecting brains.
+    const NAME: &'static str;
+
+    /// The components of the creature which the actuator reads or changes.
+    type Data: QueryData;
+
+    /// Anything else the actuator reads or changes, such as resources.
+    type Param: SystemParam;
+
+    /// Applies the output to a single creature whose brain has an action neuron for it.
+    fn act(
+        activation: f32,
+        creature: QueryItem<Self::Data>,
+        param: &mut SystemParamItem<Self::Param>,
+    );
+}
+
+/// Registers a [Sensor], and senses it for every creature whenever brains are updated.
+///
+/// Inputs are numbered in the order in which their plugins are added, which decides the brain built from a genome.
+pub struct SensorPlugin<S: Sensor> {
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S: Sensor> Default for SensorPlugin<S> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Sensor> Plugin for SensorPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NeuronRegistry>();
+
+        let input = app
+            .world_mut()
+            .resource_mut::<NeuronRegistry>()
+            .register_sensory_input(S::NAME);
+
+        app.insert_resource(SensorSlot::<S> {
+            input,
+            marker: PhantomData,
+        });
+
+        app.add_systems(FixedUpdate, sense::<S>.in_set(BrainSet::Sense));
+    }
+}
+
+/// Registers an [Actuator], and applies it to every creature whenever brains are updated.
+///
+/// Outputs are numbered in the order in which their plugins are added, which decides the brain built from a genome.
+/// Actuators are also applied in that order, so that two which change the same component always do so in the same
+/// order.
+pub struct ActuatorPlugin<A: Actuator> {
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A: Actuator> Default for ActuatorPlugin<A> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Actuator> Plugin for ActuatorPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NeuronRegistry>();
+
+        let output = app
+            .world_mut()
+            .resource_mut::<NeuronRegistry>()
+            .register_action_output(A::NAME);
+
+        app.insert_resource(ActuatorSlot::<A> {
+            output,
+            marker: PhantomData,
+        });
+
+        let index = output.index();
+
+        if index == 0 {
+            app.add_systems(
+                FixedUpdate,
+                act::<A>.in_set(BrainSet::Act).in_set(ActuatorOrder(index)),
+            );
+        } else {
+            app.add_systems(
+                FixedUpdate,
+                act::<A>
+                    .in_set(BrainSet::Act)
+                    .in_set(ActuatorOrder(index))
+                    .after(ActuatorOrder(index - 1)),
+            );
+        }
+    }
+}
+
+/// Orders actuators by their index in the registry.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct ActuatorOrder(usize);
+
+#[derive(Resource)]
+struct SensorSlot<S: Sensor> {
+    input: SensoryInput,
+    marker: PhantomData<fn() -> S>,
+}
+
+#[derive(Resource)]
+struct ActuatorSlot<A: Actuator> {
+    output: ActionOutput,
+    marker: PhantomData<fn() -> A>,
+}
+
+fn sense<S: Sensor>(
+    slot: Res<SensorSlot<S>>,
+    mut query: Query<(&mut SensoryInputs, S::Data)>,
+    param: StaticSystemParam<S::Param>,
+) {
+    for (mut sensory_inputs, creature) in &mut query {
+        sensory_inputs.set(&slot.input, S::sense(creature, &param));
+    }
+}
+
+fn act<A: Actuator>(
+    slot: Res<ActuatorSlot<A>>,
+    mut query: Query<(&ActionOutputs, A::Data)>,
+    param: StaticSystemParam<A::Param>,
+) {
+    let mut param = param.into_inner();
+
+    for (action_outputs, creature) in &mut query {
+        if let Some(activation) = action_outputs.activation(&slot.output) {
+            A::act(activation, creature, &mut param);
+        }
+    }
+}
+
+impl NeuronRegistry {
+    /// Returns a registry with only the built-in senses and actions, as used by every simulation.
+    pub fn builtin() -> Self {
+        let mut app = App::new();
+        app.add_plugins(BuiltinNeuronsPlugin);
+
+        app.world_mut()
+            .remove_resource::<NeuronRegistry>()
+            .expect("The built-in neurons are always registered.")
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+
+use super::{
+    AngularVelocity, Contact, Force, Lineage, SimulationConfig, Torque, Velocity,
+    spatial_index::{ObjectCategory, SpatialIndex},
+};
+use crate::model::creature::brain::Brain;
+
+/// Stops every creature exerting itself, so that only the actions of its latest brain update push it.
+pub(super) fn clear_forces(mut query: Query<(&mut Force, &mut Torque)>) {
+    for (mut force, mut torque) in &mut query {
+        force.value = Vec2::ZERO;
+        torque.value = 0.0;
+    }
+}
+
+/// Forgets every collision, so that only those since the latest brain update are sensed.
+pub(super) fn clear_contacts(mut query: Query<&mut Contact>) {
+    for mut contact in &mut query {
+        contact.value = false;
+    }
+}
+
+/// Accelerates every creature by the force and torque it is exerting, slows it by drag, and keeps it within the top
+/// speeds, over a single fixed timestep.
+pub(super) fn integrate_forces(
+    mut query: Query<(&mut Velocity, &mut AngularVelocity, &Force, &Torque)>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+) {
+    let physics = &config.physics;
+    let delta = time.delta_secs();
+
+    // Drag is applied exactly, rather than linearly, so that it can never reverse a creature however strong it is.
+    let linear_damping = (-physics.linear_drag * delta).exp();
+    let angular_damping = (-physics.angular_drag * delta).exp();
+
+    for (mut velocity, mut angular_velocity, force, torque) in &mut query {
+        // A brain with non-finite weights can choose a non-finite force, which would leave the velocity without a
+        // length to limit, so it is ignored.
+        if force.value.is_finite() {
+            velocity.value += force.value / physics.mass * delta;
+        }
+
+        if torque.value.is_finite() {
+            angular_velocity.value += torque.value / physics.mass * delta;
+        }
+
+        velocity.value = (velocity.value * linear_damping).clamp_length_max(physics.max_speed);
+        angular_velocity.value = (angular_velocity.value * angular_damping)
+            .clamp(-physics.max_angular_speed, physics.max_angular_speed);
+    }
+}
+
+/// Pushes apart every pair of creatures whose bodies overlap, and bounces them off each other if they are moving
+/// together.
+///
+/// The spatial index is the broad phase: only creatures in neighbouring cells are checked, using their positions after
+/// this timestep's movement. Each pair is resolved once, in order of the creatures' IDs. Query order is not
+/// deterministic, and the result depends on the order in which pairs are resolved.
+pub(super) fn resolve_collisions(
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut Contact,
+            &Lineage,
+        ),
+        With<Brain>,
+    >,
+    spatial_index: Res<SpatialIndex>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+) {
+    let physics = &config.physics;
+
+    // The index was built before this timestep's movement, so it allows for both creatures of a pair having moved at
+    // the top speed, and for a radius more of being pushed by earlier collisions. A pair which this misses is caught on
+    // the next timestep, once the index has been rebuilt.
+    let reach =
+        2.0 * physics.radius + 2.0 * (physics.max_speed * time.delta_secs() + physics.radius);
+    let reach_squared = reach * reach;
+
+    let mut creatures: Vec<_> = query
+        .iter()
+        .map(|(entity, .., lineage)| (lineage.id, entity))
+        .collect();
+
+    creatures.sort();
+
+    for (id, entity) in creatures {
+        let Ok((_, transform, ..)) = query.get(entity) else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+
+        // The spatial index is built in query order too, so the candidates are sorted as well.
+        let mut candidates: Vec<_> = spatial_index
+            .neighbourhood(position.x, position.y)
+            .filter(|object| object.category == ObjectCategory::Creature)
+            .filter(|object| {
+                let (object_x, object_y) =
+                    spatial_index.nearest_image(position.x, position.y, object.x, object.y);
+
+                position.distance_squared(Vec2::new(object_x, object_y)) <= reach_squared
+            })
+            .filter_map(|object| {
+                let (.., lineage) = query.get(object.entity).ok()?;
+
+                (lineage.id > id).then_some((lineage.id, object.entity))
+            })
+            .collect();
+
+        candidates.sort();
+
+        for (_, other) in candidates {
+            let Ok(
+                [
+                    (_, mut transform, mut velocity, mut contact, _),
+                    (_, mut other_transform, mut other_velocity, mut other_contact, _),
+                ],
+            ) = query.get_many_mut([entity, other])
+            else {
+                continue;
+            };
+
+            let position = transform.translation.truncate();
+
+            let (other_x, other_y) = spatial_index.nearest_image(
+                position.x,
+                position.y,
+                other_transform.translation.x,
+                other_transform.translation.y,
+            );
+
+            let offset = Vec2::new(other_x, other_y) - position;
+            let distance = offset.length();
+            let overlap = 2.0 * physics.radius - distance;
+
+            // Creatures with non-finite positions never collide.
+            if overlap.is_nan() || overlap <= 0.0 {
+                continue;
+            }
+
+            // Creatures at exactly the same point are pushed apart along the x axis.
+            let normal = if distance > 0.0 {
+                offset / distance
+            } else {
+                Vec2::X
+            };
+
+            // Every creature has the same mass, so each is pushed back by half of the overlap, and the impulse changes
+            // both velocities equally.
+            let separation = (normal * overlap / 2.0).extend(0.0);
+
+            transform.translation -= separation;
+            other_transform.translation += separation;
+
+            let closing_speed = (velocity.value - other_velocity.value).dot(normal);
+
+            if closing_speed > 0.0 {
+                let impulse = normal * closing_speed * (1.0 + physics.restitution) / 2.0;
+
+                velocity.value -= impulse;
+                other_velocity.value += impulse;
+            }
+
+            contact.value = true;
+            other_contact.value = true;
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The source of every random decision made in the simulation.
+///
+/// Two runs which start from the same seed will play out identically.
+#[derive(Resource)]
+pub struct SimulationRng {
+    seed: u64,
+    generator: ChaCha8Rng,
+}
+
+impl SimulationRng {
+    /// Creates a new random number generator from a master seed.
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            generator: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the master seed which the generator was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of 32-bit words which have been drawn from the generator so far.
+    pub fn word_position(&self) -> u128 {
+        self.generator.get_word_pos()
+    }
+
+    /// Recreates a generator from its master seed, in the state it was in after `word_position` words were drawn.
+    pub fn from_seed_at(seed: u64, word_position: u128) -> Self {
+        let mut rng = Self::from_seed(seed);
+        rng.generator.set_word_pos(word_position);
+        rng
+    }
+}
+
+impl Default for SimulationRng {
+    /// Creates a new random number generator from a randomly chosen seed.
+    fn default() -> Self {
+        Self::from_seed(rand::random())
+    }
+}
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        self.generator.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.generator.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generator.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.generator.try_fill_bytes(dest)
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::{prelude::*, render::camera::ScalingMode};
 
-use crate::simulation::FIXED_UPDATE_FREQUENCY;
+use crate::simulation::SimulationConfig;
 
 pub struct SetupPlugin;
 
@@ -10,7 +10,15 @@ impl Plugin for SetupPlugin {
 
         app.insert_resource(ClearColor(Color::WHITE));
 
-        app.insert_resource(Time::<Fixed>::from_hz(FIXED_UPDATE_FREQUENCY));
+        app.init_resource::<SimulationConfig>();
+
+        let fixed_update_frequency = app
+            .world()
+            .resource::<SimulationConfig>()
+            .time
+            .fixed_update_frequency;
+
+        app.insert_resource(Time::<Fixed>::from_hz(fixed_update_frequency));
 
         app.add_systems(FixedUpdate, camera_movement_controls);
     }
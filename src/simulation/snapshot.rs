@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display, fs, path::Path, path::PathBuf, time::Duration};
+
+use super::{
+    AngularVelocity, Contact, Force, SimulationConfig, SimulationRng, Torque, Velocity,
+    creature::{Age, CreatureBundle, CreatureIds, Energy, Lineage, sanitise},
+    food::{Food, FoodBundle, FoodKind},
+    species::{Species, SpeciesId, SpeciesRegistry},
+};
+use crate::model::creature::{
+    brain::{ActionOutputs, Brain, NeuronRegistry, SensoryInputs},
+    genome::Genome,
+};
+
+/// The version of the snapshot file format. This must be increased whenever the format changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A complete record of a simulated world, from which the simulation can be resumed exactly where it left off.
+///
+/// Snapshots are written as RON, which, unlike JSON, can represent the non-finite numbers which mutation is able to
+/// produce.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    /// The simulated time which had elapsed when the snapshot was taken.
+    elapsed: Duration,
+    config: SimulationConfig,
+    rng: RngSnapshot,
+    /// The ID which will be given to the next creature to be born.
+    next_creature_id: u64,
+    /// The species which were alive when the population was last clustered.
+    species: SpeciesRegistry,
+    creatures: Vec<CreatureSnapshot>,
+    food: Vec<FoodSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RngSnapshot {
+    seed: u64,
+    /// The high and low halves of the number of words drawn from the generator.
+    word_position: (u64, u64),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreatureSnapshot {
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    velocity: [f32; 2],
+    angular_velocity: f32,
+    /// The force and torque which the creature's latest brain update chose, which it exerts until the next one.
+    force: [f32; 2],
+    torque: f32,
+    /// Whether the creature has collided with another since its brain was last updated.
+    contact: bool,
+    energy: f32,
+    age: f32,
+    genome: Genome,
+    lineage: Lineage,
+    species: Option<SpeciesId>,
+    /// The activation of every neuron in the compiled brain, which recurrent connections read from.
+    brain_state: Vec<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FoodSnapshot {
+    translation: [f32; 3],
+    kind: FoodKind,
+    nutrition: f32,
+}
+
+/// Only the version is read at first, so that snapshots in other formats can be reported clearly.
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+/// Marks a world which was restored from a snapshot, rather than being populated from scratch.
+#[derive(Resource)]
+pub struct RestoredFromSnapshot;
+
+impl Snapshot {
+    /// Records the current state of the world.
+    pub fn capture(world: &mut World) -> Self {
+        let creatures = world
+            .query::<(
+                &Transform,
+                &Velocity,
+                &AngularVelocity,
+                &Force,
+                &Torque,
+                &Contact,
+                &Energy,
+                &Age,
+                &Genome,
+                &Lineage,
+                Option<&Species>,
+                &Brain,
+            )>()
+            .iter(world)
+            .map(
+                |(
+                    transform,
+                    velocity,
+                    angular_velocity,
+                    force,
+                    torque,
+                    contact,
+                    energy,
+                    age,
+                    genome,
+                    lineage,
+                    species,
+                    brain,
+                )| {
+                    CreatureSnapshot {
+                        translation: transform.translation.to_array(),
+                        rotation: transform.rotation.to_array(),
+                        velocity: velocity.value.to_array(),
+                        angular_velocity: angular_velocity.value,
+                        force: force.value.to_array(),
+                        torque: torque.value,
+                        contact: contact.value,
+                        energy: energy.value,
+                        age: age.value,
+                        genome: genome.clone(),
+                        lineage: lineage.clone(),
+                        species: species.map(|species| species.id),
+                        brain_state: brain.state().to_vec(),
+                    }
+                },
+            )
+            .collect();
+
+        let food = world
+            .query::<(&Transform, &Food)>()
+            .iter(world)
+            .map(|(transform, food)| FoodSnapshot {
+                translation: transform.translation.to_array(),
+                kind: food.kind,
+                nutrition: food.nutrition,
+            })
+            .collect();
+
+        let rng = world.resource::<SimulationRng>();
+        let word_position = rng.word_position();
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            elapsed: world.resource::<Time<Fixed>>().elapsed(),
+            config: world.resource::<SimulationConfig>().clone(),
+            rng: RngSnapshot {
+                seed: rng.seed(),
+                word_position: ((word_position >> 64) as u64, word_position as u64),
+            },
+            next_creature_id: world.resource::<CreatureIds>().next,
+            species: world
+                .get_resource::<SpeciesRegistry>()
+                .cloned()
+                .unwrap_or_default(),
+            creatures,
+            food,
+        }
+    }
+
+    /// Returns the configuration which the snapshotted world was running with.
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Returns the random number generator, in the state it was in when the snapshot was taken.
+    pub fn rng(&self) -> SimulationRng {
+        let (high, low) = self.rng.word_position;
+
+        SimulationRng::from_seed_at(self.rng.seed, ((high as u128) << 64) | low as u128)
+    }
+
+    /// Populates a world from the snapshot, and restores its clock and random number generator.
+    ///
+    /// The world's own configuration is used to rebuild each creature's brain from its genome, and to sanitise the
+    /// genome first if it asks for that. This must be called after the simulation plugins have been added, but before
+    /// the app is run.
+    pub fn restore(self, world: &mut World) {
+        let config = world.resource::<SimulationConfig>().clone();
+        let registry = world.resource::<NeuronRegistry>().clone();
+
+        world.insert_resource(self.rng());
+        world.insert_resource(CreatureIds {
+            next: self.next_creature_id,
+        });
+        world.insert_resource(self.species);
+
+        for creature in self.creatures {
+            let Some(genome) = sanitise(creature.genome, &config, &registry) else {
+                warn!(
+                    "Creature {} was not restored, as its genome is corrupt.",
+                    creature.lineage.id
+                );
+                continue;
+            };
+
+            let mut brain = Brain::new(&genome, &config.brain, &registry);
+
+            // The state is left at zero if the brain was built differently, because the configuration was replaced.
+            brain.restore_state(&creature.brain_state);
+
+            let mut entity = world.spawn(CreatureBundle {
+                transform: Transform {
+                    translation: Vec3::from_array(creature.translation),
+                    rotation: Quat::from_array(creature.rotation),
+                    ..default()
+                },
+                velocity: Velocity {
+                    value: Vec2::from_array(creature.velocity),
+                },
+                angular_velocity: AngularVelocity {
+                    value: creature.angular_velocity,
+                },
+                force: Force {
+                    value: Vec2::from_array(creature.force),
+                },
+                torque: Torque {
+                    value: creature.torque,
+                },
+                contact: Contact {
+                    value: creature.contact,
+                },
+                energy: Energy {
+                    value: creature.energy,
+                },
+                brain,
+                genome,
+                age: Age {
+                    value: creature.age,
+                },
+                lineage: creature.lineage,
+                lines_of_sight: default(),
+                sensory_inputs: SensoryInputs::default(),
+                action_outputs: ActionOutputs::default(),
+            });
+
+            if let Some(id) = creature.species {
+                entity.insert(Species { id });
+            }
+        }
+
+        for food in self.food {
+            world.spawn(FoodBundle {
+                transform: Transform::from_translation(Vec3::from_array(food.translation)),
+                food: Food {
+                    kind: food.kind,
+                    nutrition: food.nutrition,
+                },
+            });
+        }
+
+        world.resource_mut::<Time<Fixed>>().advance_to(self.elapsed);
+        world.insert_resource(RestoredFromSnapshot);
+    }
+
+    /// Writes the snapshot to a file.
+    ///
+    /// The snapshot is first written alongside the file and then moved into place, so that a process which is killed
+    /// part way through never leaves a truncated snapshot behind.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = ron::to_string(self).context("Could not serialise the snapshot.")?;
+
+        let mut partial_path = path.as_os_str().to_owned();
+        partial_path.push(".partial");
+
+        fs::write(&partial_path, contents)
+            .and_then(|()| fs::rename(&partial_path, path))
+            .with_context(|| format!("Could not write the snapshot {}.", path.display()))
+    }
+
+    /// Reads a snapshot from a file, checking that it is in a supported format.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the snapshot {}.", path.display()))?;
+
+        let header: SnapshotHeader = ron::from_str(&contents)
+            .with_context(|| format!("{} is not a snapshot.", path.display()))?;
+
+        if header.version != SNAPSHOT_VERSION {
+            return Err(UnsupportedSnapshotVersion {
+                version: header.version,
+            }
+            .into());
+        }
+
+        ron::from_str(&contents)
+            .with_context(|| format!("The snapshot {} is corrupt.", path.display()))
+    }
+}
+
+/// Saves a snapshot of the world when the app exits.
+pub struct SnapshotOnExitPlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for SnapshotOnExitPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SnapshotOnExitPath(self.path.clone()));
+
+        app.add_systems(Last, save_snapshot_on_exit.run_if(on_event::<AppExit>));
+    }
+}
+
+#[derive(Resource)]
+struct SnapshotOnExitPath(PathBuf);
+
+fn save_snapshot_on_exit(world: &mut World) {
+    let path = world.resource::<SnapshotOnExitPath>().0.clone();
+
+    if let Err(error) = Snapshot::capture(world).save(&path) {
+        error!("{error:?}");
+    }
+}
+
+/// An error returned when a snapshot was written in a format which this version of evolut cannot read.
+#[derive(Debug)]
+struct UnsupportedSnapshotVersion {
+    version: u32,
+}
+
+impl Display for UnsupportedSnapshotVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The snapshot is version {}, but only version {} is supported.",
+            self.version, SNAPSHOT_VERSION
+        )
+    }
+}
+
+impl Error for UnsupportedSnapshotVersion {}
@@ -1,20 +1,109 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
 
-use super::{SEEING_DISTANCE, creature::vision::VisibleObject, food::Food};
+use super::{
+    SimulationConfig, Topology,
+    creature::vision::VisibleObject,
+    food::{Food, FoodKind},
+};
 use crate::model::creature::brain::Brain;
 
 #[derive(Resource)]
 pub struct SpatialIndex {
     pub index: HashMap<(i32, i32), Vec<VisibleObject>>,
+    /// The width and height of each cell.
+    pub cell_size: f32,
+    /// How the cells wrap around, if the world is toroidal.
+    pub wrap: Option<Wrap>,
+}
+
+/// Describes how the cells of a toroidal world wrap around.
+#[derive(Clone, Copy, Debug)]
+pub struct Wrap {
+    /// The bounds of the world, which spans from minus the bounds to the bounds on both axes.
+    pub bounds: f32,
+    /// The number of cells across the world, on both axes.
+    pub cells: i32,
+}
+
+impl SpatialIndex {
+    /// Returns the coordinates of the cell which contains the given point.
+    pub fn cell_coordinates(&self, x: f32, y: f32) -> (i32, i32) {
+        match self.wrap {
+            Some(wrap) => (
+                (((x + wrap.bounds) / self.cell_size).floor() as i32).rem_euclid(wrap.cells),
+                (((y + wrap.bounds) / self.cell_size).floor() as i32).rem_euclid(wrap.cells),
+            ),
+            None => (
+                (x / self.cell_size).floor() as i32,
+                (y / self.cell_size).floor() as i32,
+            ),
+        }
+    }
+
+    /// Returns every object in the 3 by 3 grid of cells centred at the cell which contains the given point.
+    ///
+    /// In a toroidal world, the grid wraps around the edges of the world, and each cell is only visited once even if
+    /// the world is less than 3 cells across.
+    pub fn neighbourhood(&self, x: f32, y: f32) -> impl Iterator<Item = &VisibleObject> {
+        let (cell_x, cell_y) = self.cell_coordinates(x, y);
+
+        let mut cells: Vec<(i32, i32)> = Vec::with_capacity(9);
+
+        for offset_y in -1..=1 {
+            for offset_x in -1..=1 {
+                let cell = match self.wrap {
+                    Some(wrap) => (
+                        (cell_x + offset_x).rem_euclid(wrap.cells),
+                        (cell_y + offset_y).rem_euclid(wrap.cells),
+                    ),
+                    None => (cell_x + offset_x, cell_y + offset_y),
+                };
+
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+
+        cells
+            .into_iter()
+            .filter_map(move |cell| self.index.get(&cell))
+            .flatten()
+    }
+
+    /// Returns the position of the copy of an object which is nearest to the given point.
+    ///
+    /// In a toroidal world, an object near one edge is also near the opposite edge, so it may be nearer across the
+    /// seam. Otherwise, this is just the position of the object.
+    pub fn nearest_image(&self, x: f32, y: f32, object_x: f32, object_y: f32) -> (f32, f32) {
+        match self.wrap {
+            Some(wrap) => {
+                let width = wrap.bounds * 2.0;
+
+                let nearest = |from: f32, to: f32| {
+                    let difference = to - from;
+
+                    to - width * (difference / width).round()
+                };
+
+                (nearest(x, object_x), nearest(y, object_y))
+            }
+            None => (object_x, object_y),
+        }
+    }
 }
 
 pub struct SpatialIndexPlugin;
 
 impl Plugin for SpatialIndexPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationConfig>();
+
         app.insert_resource(SpatialIndex {
             index: HashMap::new(),
+            cell_size: 0.0,
+            wrap: None,
         });
 
         app.add_systems(Startup, build_spatial_index);
@@ -23,41 +112,68 @@ impl Plugin for SpatialIndexPlugin {
     }
 }
 
-#[derive(PartialEq, Eq)]
+/// The radius of every piece of food.
+const FOOD_RADIUS: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ObjectCategory {
     Creature,
-    Food,
+    Food(FoodKind),
 }
 
 pub fn build_spatial_index(
     creature_query: Query<(&Transform, Entity), With<Brain>>,
-    food_query: Query<(&Transform, Entity), With<Food>>,
+    food_query: Query<(&Transform, Entity, &Food)>,
     mut spatial_index_resource: ResMut<SpatialIndex>,
+    config: Res<SimulationConfig>,
 ) {
-    let creatures = creature_query.iter().collect();
-    let food = food_query.iter().collect();
-
-    let mut spatial_index: HashMap<(i32, i32), Vec<VisibleObject>> = HashMap::new();
+    let creatures = creature_query
+        .iter()
+        .map(|(transform, entity)| (transform, entity, ObjectCategory::Creature))
+        .collect();
+    let food = food_query
+        .iter()
+        .map(|(transform, entity, food)| (transform, entity, ObjectCategory::Food(food.kind)))
+        .collect();
+
+    // Each cell is large enough that anything a creature can see lies within its neighbourhood.
+    let mut spatial_index = match config.world.topology {
+        Topology::Toroidal => {
+            // The cells must tile the world exactly, so that those along one edge neighbour those along the other.
+            let width = config.world.bounds * 2.0;
+            let cells = ((width / (config.world.seeing_distance * 2.0)).floor() as i32).max(1);
+
+            SpatialIndex {
+                index: HashMap::new(),
+                cell_size: width / cells as f32,
+                wrap: Some(Wrap {
+                    bounds: config.world.bounds,
+                    cells,
+                }),
+            }
+        }
+        _ => SpatialIndex {
+            index: HashMap::new(),
+            cell_size: config.world.seeing_distance * 2.0,
+            wrap: None,
+        },
+    };
 
-    add_to_spatial_index(&mut spatial_index, creatures, ObjectCategory::Creature);
-    add_to_spatial_index(&mut spatial_index, food, ObjectCategory::Food);
+    add_to_spatial_index(&mut spatial_index, creatures, config.physics.radius);
+    add_to_spatial_index(&mut spatial_index, food, FOOD_RADIUS);
 
-    *spatial_index_resource = SpatialIndex {
-        index: spatial_index,
-    };
+    *spatial_index_resource = spatial_index;
 }
 
 fn add_to_spatial_index(
-    spatial_index: &mut HashMap<(i32, i32), Vec<VisibleObject>>,
-    objects: Vec<(&Transform, Entity)>,
-    category: ObjectCategory,
+    spatial_index: &mut SpatialIndex,
+    objects: Vec<(&Transform, Entity, ObjectCategory)>,
+    radius: f32,
 ) {
-    for (transform, entity) in objects {
+    for (transform, entity, category) in objects {
         let (object_x, object_y) = (transform.translation.x, transform.translation.y);
 
-        let cell_coordinates = get_cell_coordinates(object_x, object_y);
-
-        let (category, radius) = get_category_radius_pair(&category);
+        let cell_coordinates = spatial_index.cell_coordinates(object_x, object_y);
 
         let object = VisibleObject {
             x: object_x,
@@ -67,25 +183,11 @@ fn add_to_spatial_index(
             entity,
         };
 
-        match spatial_index.get_mut(&cell_coordinates) {
+        match spatial_index.index.get_mut(&cell_coordinates) {
             Some(objects) => objects.push(object),
             None => {
-                spatial_index.insert(cell_coordinates, vec![object]);
+                spatial_index.index.insert(cell_coordinates, vec![object]);
             }
         };
     }
 }
-
-fn get_category_radius_pair(category: &ObjectCategory) -> (ObjectCategory, f32) {
-    match category {
-        ObjectCategory::Creature => (ObjectCategory::Creature, 1.0),
-        ObjectCategory::Food => (ObjectCategory::Food, 0.5),
-    }
-}
-
-pub fn get_cell_coordinates(x: f32, y: f32) -> (i32, i32) {
-    (
-        (x - x.rem_euclid((SEEING_DISTANCE * 2) as f32)) as i32,
-        (y - y.rem_euclid((SEEING_DISTANCE * 2) as f32)) as i32,
-    )
-}
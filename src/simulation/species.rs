@@ -0,0 +1,353 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display};
+
+use super::{
+    SimulationConfig, SimulationSet, SpeciationConfig, SpeciationMethod, creature::Lineage, every,
+};
+use crate::model::creature::genome::Genome;
+
+/// The most times that k-medoids clustering will move its medoids before settling on the clusters it has.
+const MAX_K_MEDOIDS_ITERATIONS: usize = 10;
+
+/// Periodically clusters the living population by genome, and gives each creature a [Species].
+///
+/// Species keep their identity from one clustering to the next, as each is carried forward by a representative
+/// genome. A species is born when a cluster forms which no existing species accounts for, and goes extinct when no
+/// creature is left in it.
+pub struct SpeciationPlugin;
+
+impl Plugin for SpeciationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationConfig>();
+        app.init_resource::<SpeciesRegistry>();
+        app.add_event::<SpeciesBorn>();
+        app.add_event::<SpeciesExtinct>();
+
+        let interval = app
+            .world()
+            .resource::<SimulationConfig>()
+            .speciation
+            .interval;
+
+        // The creatures which died this tick must be gone before the population is clustered.
+        app.add_systems(
+            FixedUpdate,
+            assign_species
+                .run_if(every(interval))
+                .after(SimulationSet::Creatures),
+        );
+    }
+}
+
+/// An identifier which is unique to a species for the whole of a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SpeciesId(pub u64);
+
+impl Display for SpeciesId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The species which a creature was placed in when the population was last clustered.
+///
+/// Creatures born since then have no species yet.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Species {
+    pub id: SpeciesId,
+}
+
+/// A species which was alive when the population was last clustered.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpeciesRecord {
+    /// The genome which new members are compared against.
+    pub representative: Genome,
+    /// The simulated time, in seconds, at which the species was born.
+    pub founded: f64,
+    /// The number of creatures in the species.
+    pub size: usize,
+}
+
+/// Every species which is currently alive.
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+pub struct SpeciesRegistry {
+    species: BTreeMap<SpeciesId, SpeciesRecord>,
+    /// The ID which will be given to the next species to be born.
+    next: u64,
+}
+
+impl SpeciesRegistry {
+    /// Returns every living species.
+    pub fn species(&self) -> &BTreeMap<SpeciesId, SpeciesRecord> {
+        &self.species
+    }
+
+    fn allocate(&mut self) -> SpeciesId {
+        let id = SpeciesId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+/// Sent whenever a new species appears in the population.
+#[derive(Event)]
+pub struct SpeciesBorn {
+    pub species: SpeciesId,
+}
+
+/// Sent whenever the last members of a species are gone from the population.
+#[derive(Event)]
+pub struct SpeciesExtinct {
+    pub species: SpeciesId,
+}
+
+/// A group of creatures which make up one species.
+struct Cluster {
+    /// The existing species which the cluster continues, or nothing if it is a new species.
+    species: Option<SpeciesId>,
+    /// The position of each member in the population.
+    members: Vec<usize>,
+    /// The position, in the population, of the member whose genome will represent the species.
+    representative: usize,
+}
+
+fn assign_species(
+    mut commands: Commands,
+    creatures: Query<(Entity, &Genome, &Lineage, Option<&Species>)>,
+    mut registry: ResMut<SpeciesRegistry>,
+    mut born: EventWriter<SpeciesBorn>,
+    mut extinct: EventWriter<SpeciesExtinct>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+) {
+    let mut population: Vec<_> = creatures.iter().collect();
+
+    // Query order is not deterministic, and both methods depend on the order in which creatures are considered.
+    population.sort_by_key(|(_, _, lineage, _)| lineage.id);
+
+    let genomes: Vec<&Genome> = population.iter().map(|(_, genome, ..)| *genome).collect();
+
+    let clusters = match config.speciation.method {
+        SpeciationMethod::Threshold => {
+            cluster_by_threshold(&genomes, &registry.species, &config.speciation)
+        }
+        SpeciationMethod::KMedoids => {
+            cluster_by_k_medoids(&genomes, &registry.species, &config.speciation)
+        }
+    };
+
+    let mut survivors = BTreeMap::new();
+
+    for cluster in clusters {
+        if cluster.members.is_empty() {
+            continue;
+        }
+
+        let (id, founded) = match cluster.species {
+            Some(id) => (id, registry.species[&id].founded),
+            None => {
+                let id = registry.allocate();
+                born.send(SpeciesBorn { species: id });
+
+                (id, time.elapsed_secs_f64())
+            }
+        };
+
+        for &member in &cluster.members {
+            let (entity, .., species) = population[member];
+
+            if species.is_none_or(|species| species.id != id) {
+                commands.entity(entity).insert(Species { id });
+            }
+        }
+
+        survivors.insert(
+            id,
+            SpeciesRecord {
+                representative: genomes[cluster.representative].clone(),
+                founded,
+                size: cluster.members.len(),
+            },
+        );
+    }
+
+    for &id in registry.species.keys() {
+        if !survivors.contains_key(&id) {
+            extinct.send(SpeciesExtinct { species: id });
+        }
+    }
+
+    registry.species = survivors;
+}
+
+/// Places each creature in the species whose representative is nearest to it, as long as it is within the threshold.
+/// Creatures which are not close enough to any species found a new one, which later creatures can join.
+///
+/// The new representative of an existing species is the member which is nearest to its old one.
+fn cluster_by_threshold(
+    genomes: &[&Genome],
+    previous: &BTreeMap<SpeciesId, SpeciesRecord>,
+    config: &SpeciationConfig,
+) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    // The genome which each cluster is compared against, and how near its chosen representative is to that genome.
+    let mut anchors: Vec<(&Genome, f32)> = Vec::new();
+
+    for (&id, record) in previous {
+        clusters.push(Cluster {
+            species: Some(id),
+            members: Vec::new(),
+            representative: 0,
+        });
+        anchors.push((&record.representative, f32::INFINITY));
+    }
+
+    for (index, genome) in genomes.iter().enumerate() {
+        let nearest = anchors
+            .iter()
+            .map(|(anchor, _)| genome.distance(anchor, &config.distance))
+            .enumerate()
+            .filter(|(_, distance)| *distance <= config.threshold)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        match nearest {
+            Some((cluster, distance)) => {
+                clusters[cluster].members.push(index);
+
+                if distance < anchors[cluster].1 {
+                    clusters[cluster].representative = index;
+                    anchors[cluster].1 = distance;
+                }
+            }
+            None => {
+                clusters.push(Cluster {
+                    species: None,
+                    members: vec![index],
+                    representative: index,
+                });
+                anchors.push((genome, 0.0));
+            }
+        }
+    }
+
+    clusters
+}
+
+/// Splits the population into at most k species, each made up of the creatures nearest to one of its members, the
+/// medoid.
+///
+/// The medoids start out as the creatures nearest to the representatives of the existing species, oldest species
+/// first, so that species keep their identity. Any remaining medoids are chosen to be as far as possible from the
+/// others, and become new species. Each medoid is then repeatedly moved to the member of its cluster with the least
+/// total distance to the rest, until the clusters settle.
+fn cluster_by_k_medoids(
+    genomes: &[&Genome],
+    previous: &BTreeMap<SpeciesId, SpeciesRecord>,
+    config: &SpeciationConfig,
+) -> Vec<Cluster> {
+    let distance = |a: usize, b: usize| genomes[a].distance(genomes[b], &config.distance);
+    let k = config.k.min(genomes.len());
+
+    // The species which each medoid continues, and the position of the medoid in the population.
+    let mut medoids: Vec<(Option<SpeciesId>, usize)> = Vec::new();
+
+    for (&id, record) in previous {
+        if medoids.len() == k {
+            break;
+        }
+
+        let nearest = (0..genomes.len())
+            .filter(|index| medoids.iter().all(|(_, medoid)| medoid != index))
+            .map(|index| {
+                (
+                    index,
+                    genomes[index].distance(&record.representative, &config.distance),
+                )
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((index, _)) = nearest {
+            medoids.push((Some(id), index));
+        }
+    }
+
+    while medoids.len() < k {
+        let farthest = (0..genomes.len())
+            .filter(|index| medoids.iter().all(|(_, medoid)| medoid != index))
+            .map(|index| {
+                let nearest_medoid = medoids
+                    .iter()
+                    .map(|&(_, medoid)| distance(index, medoid))
+                    .fold(f32::INFINITY, f32::min);
+
+                (index, nearest_medoid)
+            })
+            // The comparison is reversed, rather than using max_by, so that ties go to the earliest creature.
+            .min_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        match farthest {
+            Some((index, _)) => medoids.push((None, index)),
+            None => break,
+        }
+    }
+
+    let mut iterations = 0;
+
+    loop {
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); medoids.len()];
+
+        for index in 0..genomes.len() {
+            let nearest = medoids
+                .iter()
+                .map(|&(_, medoid)| distance(index, medoid))
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            if let Some((cluster, _)) = nearest {
+                members[cluster].push(index);
+            }
+        }
+
+        let mut moved = false;
+
+        if iterations < MAX_K_MEDOIDS_ITERATIONS {
+            for ((_, medoid), members) in medoids.iter_mut().zip(&members) {
+                let total_distance = |candidate: usize| -> f32 {
+                    members
+                        .iter()
+                        .map(|&member| distance(candidate, member))
+                        .sum()
+                };
+
+                let best = members
+                    .iter()
+                    .map(|&candidate| (candidate, total_distance(candidate)))
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+                // Medoids only move to strictly better members, so that ties cannot keep the clusters from settling.
+                if let Some((best, best_total)) = best
+                    && best_total < total_distance(*medoid)
+                {
+                    *medoid = best;
+                    moved = true;
+                }
+            }
+        }
+
+        iterations += 1;
+
+        if !moved {
+            return medoids
+                .into_iter()
+                .zip(members)
+                .map(|((species, medoid), members)| Cluster {
+                    species,
+                    members,
+                    representative: medoid,
+                })
+                .collect();
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use bevy::prelude::*;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    Food,
+    creature::{Age, CreatureBorn, CreatureDied, Energy},
+    every,
+    snapshot::RestoredFromSnapshot,
+    species::{SpeciesBorn, SpeciesExtinct, SpeciesRegistry},
+};
+use crate::model::creature::{brain::Brain, genome::Genome};
+
+/// Samples statistics about the population at a regular interval of simulated time, and streams them to a file.
+///
+/// A fresh run replaces any existing file. A run which was restored from a snapshot appends to it instead, so that the
+/// statistics from before the snapshot are kept. Any samples taken after the snapshot, by the run which was
+/// interrupted, are removed first, as the resumed run takes them again. If the file cannot be opened, the error is
+/// logged and no statistics are written.
+pub struct StatisticsPlugin {
+    pub path: PathBuf,
+    pub format: StatisticsFormat,
+    /// The time, in seconds, between each sample.
+    pub interval: f64,
+}
+
+/// The formats which statistics can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatisticsFormat {
+    /// Comma-separated values, with a header row. Missing values are left empty.
+    Csv,
+    /// One JSON object per line. Missing values are null.
+    JsonLines,
+}
+
+impl StatisticsFormat {
+    /// Chooses a format from the extension of a file: either `.csv` or `.jsonl`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "jsonl" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StatisticsWriter {
+            path: self.path.clone(),
+            writer: None,
+            format: self.format,
+            header_written: false,
+        });
+        app.init_resource::<EventCounts>();
+        app.add_event::<SpeciesBorn>();
+        app.add_event::<SpeciesExtinct>();
+
+        app.add_systems(Startup, open_statistics_file);
+        app.add_systems(
+            FixedLast,
+            (count_events, record_sample.run_if(every(self.interval))).chain(),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct StatisticsWriter {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    format: StatisticsFormat,
+    header_written: bool,
+}
+
+/// The births and deaths, of creatures and of species, since the previous sample.
+#[derive(Resource, Default)]
+struct EventCounts {
+    births: u32,
+    deaths: u32,
+    species_births: u32,
+    species_extinctions: u32,
+}
+
+/// A summary of the population at a moment in time.
+///
+/// Values which are undefined for an empty population are left out.
+#[derive(Serialize)]
+struct Sample {
+    /// The simulated time, in seconds.
+    time: f64,
+    population: usize,
+    births: u32,
+    deaths: u32,
+    energy_mean: Option<f32>,
+    energy_min: Option<f32>,
+    energy_max: Option<f32>,
+    age_mean: Option<f32>,
+    age_min: Option<f32>,
+    age_max: Option<f32>,
+    food: usize,
+    genome_length_mean: Option<f32>,
+    /// The mean number of connections in each brain.
+    brain_size_mean: Option<f32>,
+    /// The number of species found when the population was last clustered.
+    species: usize,
+    species_births: u32,
+    species_extinctions: u32,
+}
+
+const CSV_HEADER: &str = "time,population,births,deaths,energy_mean,energy_min,energy_max,age_mean,age_min,age_max,food,genome_length_mean,brain_size_mean,species,species_births,species_extinctions";
+
+impl Sample {
+    fn csv_row(&self) -> String {
+        let optional =
+            |value: Option<f32>| value.map(|value| value.to_string()).unwrap_or_default();
+
+        [
+            self.time.to_string(),
+            self.population.to_string(),
+            self.births.to_string(),
+            self.deaths.to_string(),
+            optional(self.energy_mean),
+            optional(self.energy_min),
+            optional(self.energy_max),
+            optional(self.age_mean),
+            optional(self.age_min),
+            optional(self.age_max),
+            self.food.to_string(),
+            optional(self.genome_length_mean),
+            optional(self.brain_size_mean),
+            self.species.to_string(),
+            self.species_births.to_string(),
+            self.species_extinctions.to_string(),
+        ]
+        .join(",")
+    }
+}
+
+/// The mean, minimum and maximum of some values, or nothing if there are no values.
+fn summarise(values: impl Iterator<Item = f32>) -> (Option<f32>, Option<f32>, Option<f32>) {
+    let mut count = 0;
+    let mut sum = 0.0;
+    let mut min = f32::INFINITY;
+    let mut max = f32::NEG_INFINITY;
+
+    for value in values {
+        count += 1;
+        sum += value;
+        min = min.min(value);
+        max = max.max(value);
+    }
+
+    if count == 0 {
+        (None, None, None)
+    } else {
+        (Some(sum / count as f32), Some(min), Some(max))
+    }
+}
+
+fn open_statistics_file(
+    mut statistics: ResMut<StatisticsWriter>,
+    restored: Option<Res<RestoredFromSnapshot>>,
+    time: Res<Time<Fixed>>,
+) {
+    let file = if restored.is_some() {
+        discard_samples_after(&statistics.path, statistics.format, time.elapsed_secs_f64())
+            .and_then(|()| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&statistics.path)
+            })
+    } else {
+        File::create(&statistics.path)
+    };
+
+    match file {
+        Ok(file) => {
+            // A CSV file which is being appended to already has its header.
+            statistics.header_written = file.metadata().is_ok_and(|metadata| metadata.len() > 0);
+            statistics.writer = Some(BufWriter::new(file));
+        }
+        Err(error) => error!(
+            "Could not open the statistics file {}: {error}",
+            statistics.path.display()
+        ),
+    }
+}
+
+/// Removes every sample taken after a point in time from a statistics file.
+///
+/// The file is rewritten alongside the original and then moved into place, so that a process which is killed part way
+/// through never loses the samples which are kept.
+fn discard_samples_after(path: &Path, format: StatisticsFormat, time: f64) -> io::Result<()> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+
+    let mut kept = String::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let sample_time = match format {
+            // The header is always kept.
+            StatisticsFormat::Csv if index == 0 => Some(f64::NEG_INFINITY),
+            StatisticsFormat::Csv => line.split(',').next().and_then(|time| time.parse().ok()),
+            StatisticsFormat::JsonLines => serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|sample| sample["time"].as_f64()),
+        };
+
+        if sample_time.is_some_and(|sample_time| sample_time <= time) {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+
+    let mut partial_path = path.as_os_str().to_owned();
+    partial_path.push(".partial");
+
+    fs::write(&partial_path, kept).and_then(|()| fs::rename(&partial_path, path))
+}
+
+fn count_events(
+    mut births: EventReader<CreatureBorn>,
+    mut deaths: EventReader<CreatureDied>,
+    mut species_births: EventReader<SpeciesBorn>,
+    mut species_extinctions: EventReader<SpeciesExtinct>,
+    mut counts: ResMut<EventCounts>,
+) {
+    counts.births += births.read().count() as u32;
+    counts.deaths += deaths.read().count() as u32;
+    counts.species_births += species_births.read().count() as u32;
+    counts.species_extinctions += species_extinctions.read().count() as u32;
+}
+
+fn record_sample(
+    creatures: Query<(&Energy, &Age, &Genome, &Brain)>,
+    food: Query<(), With<Food>>,
+    species: Option<Res<SpeciesRegistry>>,
+    time: Res<Time<Fixed>>,
+    mut counts: ResMut<EventCounts>,
+    mut statistics: ResMut<StatisticsWriter>,
+) {
+    let (energy_mean, energy_min, energy_max) =
+        summarise(creatures.iter().map(|(energy, ..)| energy.value));
+    let (age_mean, age_min, age_max) = summarise(creatures.iter().map(|(_, age, ..)| age.value));
+    let (genome_length_mean, ..) = summarise(
+        creatures
+            .iter()
+            .map(|(_, _, genome, _)| genome.genes().len() as f32),
+    );
+    let (brain_size_mean, ..) = summarise(
+        creatures
+            .iter()
+            .map(|(.., brain)| brain.connection_count() as f32),
+    );
+
+    let sample = Sample {
+        time: time.elapsed_secs_f64(),
+        population: creatures.iter().len(),
+        births: counts.births,
+        deaths: counts.deaths,
+        energy_mean,
+        energy_min,
+        energy_max,
+        age_mean,
+        age_min,
+        age_max,
+        food: food.iter().len(),
+        genome_length_mean,
+        brain_size_mean,
+        species: species.map_or(0, |species| species.species().len()),
+        species_births: counts.species_births,
+        species_extinctions: counts.species_extinctions,
+    };
+
+    *counts = EventCounts::default();
+
+    if let Err(error) = statistics.write(&sample) {
+        error!("Could not write statistics: {error}");
+    }
+}
+
+impl StatisticsWriter {
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        let Some(writer) = &mut self.writer else {
+            return Ok(());
+        };
+
+        match self.format {
+            StatisticsFormat::Csv => {
+                if !self.header_written {
+                    writeln!(writer, "{CSV_HEADER}")?;
+                    self.header_written = true;
+                }
+
+                writeln!(writer, "{}", sample.csv_row())?;
+            }
+            StatisticsFormat::JsonLines => {
+                serde_json::to_writer(&mut *writer, sample)?;
+                writeln!(writer)?;
+            }
+        }
+
+        // Flushing after every sample means that the file is complete up to the latest sample, even if the run is
+        // killed.
+        writer.flush()
+    }
+}
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+/// Stops the app once the simulation has run for a given number of fixed timesteps.
+pub struct TickLimitPlugin {
+    pub ticks: u64,
+}
+
+impl Plugin for TickLimitPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TickLimit { ticks: self.ticks });
+
+        app.add_systems(FixedLast, stop_at_tick_limit);
+    }
+}
+
+#[derive(Resource)]
+struct TickLimit {
+    ticks: u64,
+}
+
+fn stop_at_tick_limit(
+    limit: Res<TickLimit>,
+    mut elapsed_ticks: Local<u64>,
+    mut exit: EventWriter<AppExit>,
+) {
+    *elapsed_ticks += 1;
+
+    if *elapsed_ticks == limit.ticks {
+        exit.send(AppExit::Success);
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use super::{RepulsiveBorder, SimulationConfig, Topology, Velocity, WallCollision};
+
+impl Topology {
+    /// Brings a position which has left the world back into it, or pushes it back towards it, and adjusts the velocity
+    /// to match. Positions within the world are left alone.
+    ///
+    /// The world spans from minus the bounds to the bounds on both axes.
+    pub fn confine(&self, bounds: f32, position: &mut Vec2, velocity: &mut Vec2, delta: f32) {
+        for axis in 0..2 {
+            let (position, velocity) = (&mut position[axis], &mut velocity[axis]);
+
+            match self {
+                Topology::Walls(walls) => hit_walls(walls.collision, bounds, position, velocity),
+                Topology::Toroidal => wrap(bounds, position),
+                Topology::Repulsive(border) => repel(border, bounds, *position, velocity, delta),
+            }
+        }
+    }
+}
+
+fn hit_walls(collision: WallCollision, bounds: f32, position: &mut f32, velocity: &mut f32) {
+    if position.abs() <= bounds {
+        return;
+    }
+
+    let wall = bounds.copysign(*position);
+
+    match collision {
+        WallCollision::Reflect => {
+            *position = 2.0 * wall - *position;
+            *velocity = -velocity.abs().copysign(wall);
+        }
+        WallCollision::Stop => {
+            if velocity.signum() == wall.signum() {
+                *velocity = 0.0;
+            }
+        }
+    }
+
+    // A creature moving fast enough could be reflected past the opposite wall.
+    *position = position.clamp(-bounds, bounds);
+}
+
+fn wrap(bounds: f32, position: &mut f32) {
+    if *position < -bounds || *position >= bounds {
+        *position = (*position + bounds).rem_euclid(2.0 * bounds) - bounds;
+    }
+}
+
+fn repel(border: &RepulsiveBorder, bounds: f32, position: f32, velocity: &mut f32, delta: f32) {
+    let excess = position.abs() - bounds;
+
+    if excess > 0.0 {
+        *velocity -= (excess * border.strength * delta).copysign(position);
+    }
+}
+
+/// Keeps every creature within the world, as its topology dictates.
+pub(super) fn confine_to_world(
+    mut query: Query<(&mut Transform, &mut Velocity)>,
+    time: Res<Time<Fixed>>,
+    config: Res<SimulationConfig>,
+) {
+    let bounds = config.world.bounds;
+
+    for (mut transform, mut velocity) in &mut query {
+        let mut position = transform.translation.truncate();
+
+        config.world.topology.confine(
+            bounds,
+            &mut position,
+            &mut velocity.value,
+            time.delta_secs(),
+        );
+
+        if position != transform.translation.truncate() {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use rand::Rng;
+use std::{collections::HashMap, sync::Arc};
+
+use evolut::{
+    model::creature::{
+        brain::{
+            ActionOutput, Activation, Brain, BrainConfig, CycleHandling, InternalNeuron, Neuron,
+            NeuronRegistry, SensoryInputs,
+        },
+        genome::{Gene, Genome},
+    },
+    simulation::SimulationRng,
+};
+
+/// Evaluates a brain by walking its tree, as the simulation did before brains were compiled, with floats as bits.
+fn evaluate_tree(brain: &Brain, sensory_inputs: &SensoryInputs) -> Vec<(ActionOutput, u32)> {
+    let mut internal_activation_cache: HashMap<Arc<InternalNeuron>, f32> = HashMap::new();
+
+    brain
+        .neurons()
+        .iter()
+        .filter_map(|neuron| match neuron {
+            Neuron::Action(action_neuron) => Some((
+                *action_neuron.output(),
+                action_neuron
+                    .activation(&mut internal_activation_cache, sensory_inputs)
+                    .to_bits(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn evaluate_compiled(
+    brain: &mut Brain,
+    sensory_inputs: &SensoryInputs,
+) -> Vec<(ActionOutput, u32)> {
+    brain
+        .evaluate(sensory_inputs)
+        .map(|(output, activation)| (output, activation.to_bits()))
+        .collect()
+}
+
+/// The id of the identity activation function, so that activations are easy to follow by hand.
+const IDENTITY: u8 = 3;
+
+/// Internal neurons A and B, where the input feeds into A, A into B and B into the output. B also feeds back into A.
+fn two_neuron_cycle() -> Genome {
+    Genome::new(vec![
+        Gene::new(0, 128, 1.0, 0.0, IDENTITY),
+        Gene::new(129, 128, 0.5, 0.0, IDENTITY),
+        Gene::new(128, 129, 2.0, 0.0, IDENTITY),
+        Gene::new(129, 0, 1.0, 0.0, IDENTITY),
+    ])
+}
+
+/// An internal neuron between the input and the output, which also feeds into itself.
+fn self_connection() -> Genome {
+    Genome::new(vec![
+        Gene::new(0, 128, 1.0, 0.0, IDENTITY),
+        Gene::new(128, 128, 0.5, 0.0, IDENTITY),
+        Gene::new(128, 0, 1.0, 0.0, IDENTITY),
+    ])
+}
+
+/// A registry with a single input and a single output.
+fn registry() -> NeuronRegistry {
+    let mut registry = NeuronRegistry::default();
+
+    registry.register_sensory_input("Input");
+    registry.register_action_output("Output");
+
+    registry
+}
+
+fn brain(genome: &Genome, cycles: CycleHandling) -> Brain {
+    let config = BrainConfig {
+        cycles,
+        ..Default::default()
+    };
+
+    Brain::new(genome, &config, &registry())
+}
+
+/// Evaluates a brain several times, with an input of one, and returns the output of each evaluation.
+fn outputs(brain: &mut Brain, evaluations: usize) -> Vec<f32> {
+    let sensory_inputs = SensoryInputs::new(vec![1.0]);
+
+    (0..evaluations)
+        .map(|_| {
+            let outputs: Vec<f32> = brain
+                .evaluate(&sensory_inputs)
+                .map(|(_, activation)| activation)
+                .collect();
+
+            assert_eq!(outputs.len(), 1);
+
+            outputs[0]
+        })
+        .collect()
+}
+
+#[test]
+fn recurrent_cycles_read_the_previous_evaluation() {
+    let mut brain = brain(&two_neuron_cycle(), CycleHandling::Recurrent);
+
+    // A is the input plus half of B from the previous evaluation, and B is double A.
+    assert_eq!(brain.connection_count(), 4);
+    assert_eq!(outputs(&mut brain, 3), vec![2.0, 4.0, 6.0]);
+}
+
+#[test]
+fn recurrent_self_connections_read_the_previous_evaluation() {
+    let mut brain = brain(&self_connection(), CycleHandling::Recurrent);
+
+    assert_eq!(brain.connection_count(), 3);
+    assert_eq!(outputs(&mut brain, 3), vec![1.0, 1.5, 1.75]);
+}
+
+#[test]
+fn discarded_cycles_leave_brains_without_memory() {
+    let mut cycle = brain(&two_neuron_cycle(), CycleHandling::Discard);
+
+    assert_eq!(cycle.connection_count(), 3);
+    assert_eq!(outputs(&mut cycle, 3), vec![2.0, 2.0, 2.0]);
+
+    let mut self_connection = brain(&self_connection(), CycleHandling::Discard);
+
+    assert_eq!(self_connection.connection_count(), 2);
+    assert_eq!(outputs(&mut self_connection, 3), vec![1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn each_neuron_takes_its_bias_once_from_the_first_gene_leading_into_it() {
+    let genome = Genome::new(vec![
+        Gene::new(0, 128, 1.0, -0.5, IDENTITY),
+        Gene::new(0, 128, 1.0, 9.0, IDENTITY),
+        Gene::new(128, 0, 1.0, 0.25, IDENTITY),
+        Gene::new(0, 0, 2.0, 9.0, IDENTITY),
+    ]);
+
+    let mut brain = brain(&genome, CycleHandling::Discard);
+
+    // The internal neuron is 1 + 1 - 0.5, and the output is 1.5 + 2 + 0.25, however many inputs each has.
+    assert_eq!(outputs(&mut brain, 1), vec![3.75]);
+    assert_eq!(
+        evaluate_tree(&brain, &SensoryInputs::new(vec![1.0])),
+        vec![(registry().action_outputs()[0], 3.75f32.to_bits())]
+    );
+}
+
+#[test]
+fn restored_state_carries_the_memory_of_a_brain() {
+    let mut original = brain(&self_connection(), CycleHandling::Recurrent);
+    outputs(&mut original, 2);
+
+    let mut restored = brain(&self_connection(), CycleHandling::Recurrent);
+
+    assert!(restored.restore_state(original.state()));
+    assert_eq!(outputs(&mut restored, 1), outputs(&mut original, 1));
+}
+
+#[test]
+fn state_of_the_wrong_length_is_rejected() {
+    let mut brain = brain(&self_connection(), CycleHandling::Recurrent);
+    outputs(&mut brain, 2);
+
+    let state = brain.state().to_vec();
+
+    for length in [0, state.len() - 1, state.len() + 1] {
+        assert!(!brain.restore_state(&vec![9.0; length]));
+        assert_eq!(brain.state(), state);
+    }
+}
+
+#[test]
+fn compiled_brains_match_their_trees_bit_for_bit() {
+    let config = BrainConfig::default();
+    let registry = NeuronRegistry::builtin();
+
+    let mut evaluated_internal_neurons = false;
+
+    for seed in 0..500 {
+        let mut generator = SimulationRng::from_seed(seed);
+
+        let length = generator.gen_range(1..80);
+        let mut brain = Brain::new(&Genome::random(length, &mut generator), &config, &registry);
+
+        evaluated_internal_neurons |= brain
+            .neurons()
+            .iter()
+            .any(|neuron| matches!(neuron, Neuron::Internal(_)));
+
+        for _ in 0..3 {
+            let sensory_inputs = SensoryInputs::new(
+                (0..registry.sensory_inputs().len())
+                    .map(|_| generator.gen_range(-100.0..100.0))
+                    .collect(),
+            );
+
+            assert_eq!(
+                evaluate_compiled(&mut brain, &sensory_inputs),
+                evaluate_tree(&brain, &sensory_inputs),
+                "seed {seed}"
+            );
+        }
+    }
+
+    assert!(evaluated_internal_neurons);
+}
@@ -0,0 +1,59 @@
+use evolut::model::creature::{
+    brain::{Brain, BrainConfig, CycleHandling, NeuronRegistry, NodeKind},
+    genome::Genome,
+};
+
+/// Age feeds into an internal neuron, which feeds into acceleration, and into itself.
+const GENOME: &str = "genome:v2:80003f8000003dcccccd020080bf80000000000000058080400000000000000000";
+
+fn brain(config: &BrainConfig) -> Brain {
+    Brain::new(
+        &GENOME.parse::<Genome>().unwrap(),
+        config,
+        &NeuronRegistry::builtin(),
+    )
+}
+
+#[test]
+fn every_neuron_and_connection_is_exported() {
+    let graph = brain(&BrainConfig::default()).graph();
+
+    let kinds: Vec<NodeKind> = graph.nodes.iter().map(|node| node.kind).collect();
+
+    assert_eq!(
+        kinds,
+        vec![NodeKind::Action, NodeKind::Internal, NodeKind::Sensory]
+    );
+    assert_eq!(graph.edges.len(), 2);
+
+    let dot = graph.to_dot();
+
+    assert!(dot.starts_with("digraph brain {"));
+    assert!(dot.contains("\"Age\" -> \"Internal 0\" [label=\"-1.0000\", color=firebrick"));
+    assert!(dot.contains("\"Internal 0\" -> \"Acceleration\" [label=\"+1.0000\""));
+    assert!(dot.contains("\"Acceleration\" [label=\"Acceleration\\nReLU +0.1000\""));
+
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+
+    assert_eq!(json["nodes"][1]["function"], "sin");
+    assert_eq!(json["nodes"][1]["bias"], 0.0);
+    assert_eq!(json["nodes"][2]["bias"], serde_json::Value::Null);
+    assert_eq!(json["edges"][0]["weight"], -1.0);
+}
+
+#[test]
+fn recurrent_connections_are_dashed() {
+    let config = BrainConfig {
+        cycles: CycleHandling::Recurrent,
+        ..Default::default()
+    };
+
+    let graph = brain(&config).graph();
+
+    assert_eq!(graph.edges.iter().filter(|edge| edge.recurrent).count(), 1);
+    assert!(
+        graph
+            .to_dot()
+            .contains("\"Internal 0\" -> \"Internal 0\" [label=\"+2.0000\", color=forestgreen, penwidth=3.00, style=dashed]")
+    );
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use evolut::simulation::{
+    CheckpointPlugin, CreatureConfig, CreaturePlugin, FoodConfig, FoodPlugin, HeadlessPlugin,
+    NoValidCheckpoint, SimulationConfig, SimulationRng, SpatialIndexPlugin, latest_checkpoint,
+};
+
+/// An empty directory of its own for each test.
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!(
+        "evolut-checkpoint-test-{name}-{}",
+        std::process::id()
+    ));
+
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+
+    directory
+}
+
+/// Runs a tiny world which saves a checkpoint every 10 ticks, keeping the latest `keep`.
+fn run_with_checkpoints(directory: &Path, keep: usize, updates: usize) {
+    let config = SimulationConfig {
+        creatures: CreatureConfig {
+            generation_zero_size: 2,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 0,
+            growth_rate: 0.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(CheckpointPlugin {
+            directory: directory.to_path_buf(),
+            interval: Some(0.01),
+            births: None,
+            keep,
+        });
+
+    app.finish();
+    app.cleanup();
+
+    for _ in 0..updates {
+        app.update();
+    }
+}
+
+fn file_names(directory: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+
+    names.sort();
+
+    names
+}
+
+#[test]
+fn only_the_newest_checkpoints_are_kept() {
+    let directory = directory("rotation");
+
+    // The first update runs no fixed timesteps, so this saves a checkpoint at every tenth tick up to 110.
+    run_with_checkpoints(&directory, 3, 111);
+
+    // Tick 100 has more digits than tick 90, so this only holds if the ticks are zero padded.
+    assert_eq!(
+        file_names(&directory),
+        [
+            "checkpoint-000000000090.ron",
+            "checkpoint-000000000100.ron",
+            "checkpoint-000000000110.ron",
+        ]
+    );
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn corrupt_checkpoints_are_skipped() {
+    let directory = directory("corrupt");
+
+    run_with_checkpoints(&directory, 2, 21);
+
+    let newest = directory.join("checkpoint-000000000020.ron");
+    let previous = directory.join("checkpoint-000000000010.ron");
+
+    let latest = latest_checkpoint(&directory).unwrap();
+
+    assert_eq!(latest.path, newest);
+    assert!(latest.skipped.is_empty());
+
+    // As if the process had been killed part way through writing it.
+    let contents = fs::read(&newest).unwrap();
+    fs::write(&newest, &contents[..contents.len() / 2]).unwrap();
+
+    let latest = latest_checkpoint(&directory).unwrap();
+
+    assert_eq!(latest.path, previous);
+    assert_eq!(latest.skipped.len(), 1);
+    assert_eq!(latest.skipped[0].path, newest);
+    assert!(
+        latest.skipped[0]
+            .to_string()
+            .starts_with(&format!("Skipping {}: ", newest.display()))
+    );
+
+    fs::write(&previous, "").unwrap();
+
+    let error = latest_checkpoint(&directory).err().unwrap();
+    let skipped: Vec<&PathBuf> = error
+        .downcast_ref::<NoValidCheckpoint>()
+        .unwrap()
+        .skipped()
+        .iter()
+        .map(|skipped| &skipped.path)
+        .collect();
+
+    assert_eq!(skipped, [&newest, &previous]);
+    assert!(error.to_string().contains("Skipping"));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use evolut::simulation::{FoodKind, FoodPatch, SimulationConfig};
+
+#[test]
+fn the_default_config_is_valid() {
+    assert!(SimulationConfig::default().validate().is_ok());
+}
+
+#[test]
+fn the_documented_config_can_be_loaded() {
+    SimulationConfig::load(Path::new("config/default.toml")).unwrap();
+}
+
+#[test]
+fn reproduction_thresholds_must_be_positive() {
+    for threshold in [f32::NAN, f32::INFINITY, 0.0, -1.0] {
+        let mut config = SimulationConfig::default();
+
+        config.energy.reproduction_threshold = threshold;
+        config.energy.reproduction_cost = 0.0;
+
+        let problems = config.validate().unwrap_err().problems().clone();
+
+        assert!(
+            problems
+                .iter()
+                .any(|problem| problem.starts_with("energy.reproduction_threshold")),
+            "{threshold} was accepted"
+        );
+    }
+}
+
+fn problems(config: &SimulationConfig) -> Vec<String> {
+    match config.validate() {
+        Ok(()) => Vec::new(),
+        Err(invalid) => invalid.problems().clone(),
+    }
+}
+
+#[test]
+fn toxic_food_must_not_be_worth_energy() {
+    let mut config = SimulationConfig::default();
+
+    config.food.toxic.energy = 10.0;
+
+    assert_eq!(
+        problems(&config),
+        ["food.toxic.energy must not be positive, but was 10"]
+    );
+}
+
+#[test]
+fn food_which_grows_must_be_worth_something() {
+    let mut config = SimulationConfig::default();
+
+    // Carrion with no energy is simply never left behind.
+    assert_eq!(config.food.carrion.energy, 0.0);
+    assert!(problems(&config).is_empty());
+
+    config.food.plant.energy = 0.0;
+
+    assert_eq!(
+        problems(&config),
+        ["food.plant.energy must not be zero, as plant is configured to grow"]
+    );
+
+    config.food.plant.energy = 1000.0;
+    config.food.patches.push(FoodPatch {
+        x: 0.0,
+        y: 0.0,
+        radius: 10.0,
+        growth_rate: 1.0,
+        capacity: 10,
+        kind: FoodKind::Carrion,
+    });
+
+    assert_eq!(
+        problems(&config),
+        ["food.carrion.energy must not be zero, as carrion is configured to grow"]
+    );
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use evolut::{
+    model::creature::genome::Genome,
+    simulation::{
+        Age, CreatureConfig, CreaturePlugin, Energy, EnergyConfig, Food, FoodPlugin,
+        HeadlessPlugin, SimulationConfig, SimulationRng, SpatialIndexPlugin, WorldConfig,
+    },
+};
+
+/// The number of fixed timesteps to compare. This covers several brain updates.
+const TICKS: usize = 350;
+
+/// Everything about a creature which should be reproducible, with floats compared bit for bit.
+type CreatureState = (u32, u32, u32, u32, u32, Vec<String>);
+
+/// A small, crowded world, where creatures are born and die within the first few hundred ticks.
+fn config() -> SimulationConfig {
+    let mut config = SimulationConfig {
+        world: WorldConfig {
+            bounds: 50.0,
+            ..Default::default()
+        },
+        creatures: CreatureConfig {
+            generation_zero_size: 200,
+            initial_energy: 2.0,
+            ..Default::default()
+        },
+        energy: EnergyConfig {
+            reproduction_threshold: 500.0,
+            reproduction_cost: 250.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    config.food.initial = 2000;
+
+    config
+}
+
+fn build_app(seed: u64) -> App {
+    let mut app = App::new();
+
+    app.insert_resource(config())
+        .insert_resource(SimulationRng::from_seed(seed))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin);
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+fn population(app: &mut App) -> Vec<CreatureState> {
+    app.world_mut()
+        .query::<(&Transform, &Energy, &Age, &Genome)>()
+        .iter(app.world())
+        .map(|(transform, energy, age, genome)| {
+            (
+                transform.translation.x.to_bits(),
+                transform.translation.y.to_bits(),
+                transform.rotation.z.to_bits(),
+                energy.value.to_bits(),
+                age.value.to_bits(),
+                genome.genes().iter().map(|gene| gene.as_hex()).collect(),
+            )
+        })
+        .collect()
+}
+
+fn food(app: &mut App) -> Vec<(u32, u32)> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Food>>()
+        .iter(app.world())
+        .map(|transform| {
+            (
+                transform.translation.x.to_bits(),
+                transform.translation.y.to_bits(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn runs_with_the_same_seed_are_identical() {
+    let mut first = build_app(42);
+    let mut second = build_app(42);
+
+    for tick in 0..TICKS {
+        first.update();
+        second.update();
+
+        assert!(
+            population(&mut first) == population(&mut second),
+            "The populations diverged at tick {tick}."
+        );
+        assert!(
+            food(&mut first) == food(&mut second),
+            "The food diverged at tick {tick}."
+        );
+    }
+}
+
+#[test]
+fn runs_with_different_seeds_differ() {
+    let mut first = build_app(1);
+    let mut second = build_app(2);
+
+    first.update();
+    second.update();
+
+    assert!(population(&mut first) != population(&mut second));
+}
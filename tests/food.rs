@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use evolut::simulation::{
+    CreatureConfig, CreaturePlugin, Energy, Food, FoodConfig, FoodKind, FoodKindConfig, FoodPatch,
+    FoodPlugin, HeadlessPlugin, SimulationConfig, SimulationRng, SpatialIndexPlugin, WorldConfig,
+};
+
+const PATCH: FoodPatch = FoodPatch {
+    x: 10.0,
+    y: -10.0,
+    radius: 5.0,
+    growth_rate: 1000.0,
+    capacity: 20,
+    kind: FoodKind::Plant,
+};
+
+/// A world without creatures, where food only grows, quickly, in a single patch.
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        food: FoodConfig {
+            initial: 0,
+            growth_rate: 0.0,
+            patches: vec![PATCH],
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn build_app(config: SimulationConfig, creatures: bool) -> App {
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin);
+
+    if creatures {
+        app.add_plugins(CreaturePlugin);
+    }
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+fn food(app: &mut App) -> Vec<Vec2> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Food>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.truncate())
+        .collect()
+}
+
+#[test]
+fn food_grows_in_patches_up_to_their_capacity() {
+    let mut app = build_app(config(), false);
+
+    for _ in 0..1000 {
+        app.update();
+
+        assert!(food(&mut app).len() <= PATCH.capacity as usize);
+    }
+
+    let food = food(&mut app);
+
+    // Growth slows as the capacity is approached, so the patch is nearly, but not necessarily completely, full.
+    assert!(food.len() >= PATCH.capacity as usize / 2);
+
+    for position in food {
+        assert!(position.distance(Vec2::new(PATCH.x, PATCH.y)) <= PATCH.radius + 1e-4);
+    }
+}
+
+#[test]
+fn toxic_food_decays_towards_being_harmless() {
+    let mut config = config();
+
+    config.food.patches[0].kind = FoodKind::Toxic;
+    config.food.toxic = FoodKindConfig {
+        energy: -1000.0,
+        decay_rate: 100.0,
+    };
+
+    let mut app = build_app(config, false);
+
+    let mut decayed = false;
+
+    for _ in 0..1000 {
+        app.update();
+
+        let mut query = app.world_mut().query::<&Food>();
+
+        for food in query.iter(app.world()) {
+            assert_eq!(food.kind, FoodKind::Toxic);
+            assert!(food.nutrition >= -1000.0 && food.nutrition < 0.0);
+
+            decayed |= food.nutrition > -1000.0;
+        }
+    }
+
+    assert!(decayed);
+}
+
+#[test]
+fn dying_creatures_leave_carrion() {
+    // Creatures which starve almost at once, in a world where nothing grows.
+    let mut config = SimulationConfig {
+        creatures: CreatureConfig {
+            generation_zero_size: 20,
+            initial_energy: 0.05,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 0,
+            growth_rate: 0.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    config.food.carrion = FoodKindConfig {
+        energy: 500.0,
+        decay_rate: 0.0,
+    };
+
+    let mut app = build_app(config, true);
+
+    for _ in 0..50 {
+        app.update();
+    }
+
+    let mut query = app.world_mut().query::<&Food>();
+    let food: Vec<&Food> = query.iter(app.world()).collect();
+
+    assert!(!food.is_empty());
+
+    for food in food {
+        assert_eq!(food.kind, FoodKind::Carrion);
+        assert_eq!(food.nutrition, 500.0);
+    }
+}
+
+#[test]
+fn food_within_reach_of_two_creatures_is_only_eaten_once() {
+    // Two motionless creatures, in a world so small that both are always within reach of the same food.
+    let mut config = SimulationConfig {
+        world: WorldConfig {
+            bounds: 0.5,
+            ..Default::default()
+        },
+        creatures: CreatureConfig {
+            generation_zero_size: 2,
+            genome_length: 0,
+            initial_energy: 1000.0,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 0,
+            growth_rate: 0.0,
+            consumption_distance: 10.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    config.food.toxic = FoodKindConfig {
+        energy: -100.0,
+        decay_rate: 0.0,
+    };
+
+    let mut app = build_app(config.clone(), true);
+
+    app.update();
+
+    let toxic = Food::fresh(FoodKind::Toxic, &config.food);
+    app.world_mut().spawn((Transform::default(), toxic));
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert!(food(&mut app).is_empty());
+
+    let mut query = app.world_mut().query::<&Energy>();
+    let energy: Vec<f32> = query.iter(app.world()).map(|energy| energy.value).collect();
+
+    assert_eq!(energy.len(), 2);
+
+    // Apart from the little spent on staying alive, the creatures have lost the energy of one piece between them.
+    let lost = 2000.0 - energy.iter().sum::<f32>();
+
+    assert!((100.0..101.0).contains(&lost), "{lost} energy was lost");
+}
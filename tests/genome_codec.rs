@@ -0,0 +1,115 @@
+use proptest::prelude::*;
+
+use evolut::model::creature::genome::{Gene, Genome};
+
+/// Any gene at all, including those with NaN, infinite and subnormal weights and biases.
+fn any_gene() -> impl Strategy<Value = Gene> {
+    (
+        any::<u8>(),
+        any::<u8>(),
+        any::<u32>(),
+        any::<u32>(),
+        any::<u8>(),
+    )
+        .prop_map(|(source_id, destination_id, weight, bias, activation_id)| {
+            Gene::new(
+                source_id,
+                destination_id,
+                f32::from_bits(weight),
+                f32::from_bits(bias),
+                activation_id,
+            )
+        })
+}
+
+fn any_genome() -> impl Strategy<Value = Genome> {
+    prop::collection::vec(any_gene(), 0..64).prop_map(Genome::new)
+}
+
+/// Genes are compared bit for bit, so that NaN weights and biases count as equal to themselves.
+fn gene_bits(gene: &Gene) -> (u8, u8, u32, u32, u8) {
+    (
+        gene.source_id(),
+        gene.destination_id(),
+        gene.weight().to_bits(),
+        gene.bias().to_bits(),
+        gene.activation_id(),
+    )
+}
+
+fn genome_bits(genome: &Genome) -> Vec<(u8, u8, u32, u32, u8)> {
+    genome.genes().iter().map(gene_bits).collect()
+}
+
+proptest! {
+    #[test]
+    fn genes_round_trip_through_hex(gene in any_gene()) {
+        let hex = gene.as_hex();
+
+        prop_assert_eq!(hex.len(), Gene::HEX_LENGTH);
+        prop_assert_eq!(gene_bits(&Gene::from_hex(&hex).unwrap()), gene_bits(&gene));
+    }
+
+    #[test]
+    fn genes_round_trip_through_bytes(gene in any_gene()) {
+        prop_assert_eq!(gene_bits(&Gene::from_bytes(gene.to_bytes())), gene_bits(&gene));
+    }
+
+    #[test]
+    fn genomes_round_trip_through_text(genome in any_genome()) {
+        let decoded: Genome = genome.to_string().parse().unwrap();
+
+        prop_assert_eq!(genome_bits(&decoded), genome_bits(&genome));
+    }
+
+    #[test]
+    fn genomes_round_trip_through_base64(genome in any_genome()) {
+        let decoded = Genome::from_base64(&genome.to_base64()).unwrap();
+
+        prop_assert_eq!(genome_bits(&decoded), genome_bits(&genome));
+    }
+
+    #[test]
+    fn truncated_base64_genomes_are_rejected(genome in any_genome(), cut in 1..Gene::BYTES) {
+        prop_assume!(!genome.genes().is_empty());
+
+        let mut encoded = genome.to_base64();
+        // Each base64 character holds 6 bits, so removing whole characters removes whole bytes only every 4 of them.
+        encoded.truncate(encoded.len() - cut * 4 / 3);
+
+        prop_assert!(Genome::from_base64(&encoded).is_err());
+    }
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    assert!(
+        "genome:v3:00003f8000000000000000"
+            .parse::<Genome>()
+            .is_err()
+    );
+    assert!("00003f8000000000000000".parse::<Genome>().is_err());
+}
+
+#[test]
+fn version_one_genomes_have_no_bias_and_use_tanh() {
+    let genome: Genome = "genome:v1:00003f800000".parse().unwrap();
+
+    assert_eq!(genome_bits(&genome), vec![(0, 0, 1.0f32.to_bits(), 0, 0)]);
+    assert_eq!(
+        genome.to_string(),
+        "genome:v2:00003f8000000000000000".to_string()
+    );
+}
+
+#[test]
+fn signs_are_not_hex_digits() {
+    assert!(Gene::from_hex("+f003f8000000000000000").is_err());
+    assert!(Gene::from_hex("00003f80000000000000-0").is_err());
+    assert!(
+        "genome:v2:+f003f8000000000000000"
+            .parse::<Genome>()
+            .is_err()
+    );
+    assert!("genome:v1:+f003f800000".parse::<Genome>().is_err());
+}
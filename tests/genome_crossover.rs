@@ -0,0 +1,90 @@
+use evolut::{
+    model::creature::genome::{CrossoverMethod, Genome},
+    simulation::SimulationRng,
+};
+
+const METHODS: [CrossoverMethod; 3] = [
+    CrossoverMethod::SinglePoint,
+    CrossoverMethod::TwoPoint,
+    CrossoverMethod::Uniform,
+];
+
+fn hex(genome: &Genome) -> Vec<String> {
+    genome.genes().iter().map(|gene| gene.as_hex()).collect()
+}
+
+/// Crosses random parents of the given lengths over many seeds, checking every child.
+fn check_children(first_length: usize, second_length: usize) {
+    for method in METHODS {
+        let mut from_both = false;
+
+        for seed in 0..200 {
+            let mut generator = SimulationRng::from_seed(seed);
+
+            let first = Genome::random(first_length, &mut generator);
+            let second = Genome::random(second_length, &mut generator);
+            let child = first.crossover(&second, method, &mut generator);
+
+            let (first, second, child) = (hex(&first), hex(&second), hex(&child));
+
+            assert!(child.len() >= first.len().min(second.len()));
+            assert!(child.len() <= first.len().max(second.len()));
+
+            // Single-point crossover ends with the second parent, and the other methods with the first.
+            let expected_length = match method {
+                CrossoverMethod::SinglePoint => second.len(),
+                _ => first.len(),
+            };
+            assert_eq!(child.len(), expected_length, "{method:?}");
+
+            for (position, gene) in child.iter().enumerate() {
+                assert!(
+                    first.get(position) == Some(gene) || second.get(position) == Some(gene),
+                    "{method:?} moved a gene to position {position}"
+                );
+            }
+
+            let shared_length = first.len().min(second.len());
+
+            from_both |= (0..shared_length).any(|position| child[position] == first[position])
+                && (0..shared_length).any(|position| child[position] == second[position]);
+        }
+
+        // Parents without two genes in common have nothing to mix.
+        if first_length.min(second_length) >= 2 {
+            assert!(from_both, "{method:?} never mixed the parents");
+        }
+    }
+}
+
+#[test]
+fn children_of_equal_length_parents_take_each_gene_from_either_parent() {
+    check_children(12, 12);
+}
+
+#[test]
+fn children_of_unequal_length_parents_take_each_gene_from_either_parent() {
+    check_children(12, 5);
+    check_children(5, 12);
+}
+
+#[test]
+fn children_of_empty_parents_are_empty() {
+    check_children(0, 0);
+}
+
+#[test]
+fn crossover_is_reproducible() {
+    for method in METHODS {
+        let child = |seed| {
+            let mut generator = SimulationRng::from_seed(seed);
+
+            let first = Genome::random(10, &mut generator);
+            let second = Genome::random(8, &mut generator);
+
+            hex(&first.crossover(&second, method, &mut generator))
+        };
+
+        assert_eq!(child(3), child(3));
+    }
+}
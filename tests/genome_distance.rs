@@ -0,0 +1,73 @@
+use evolut::{
+    model::creature::genome::{DistanceConfig, Genome},
+    simulation::SimulationRng,
+};
+
+#[test]
+fn distance_is_symmetric() {
+    let config = DistanceConfig::default();
+
+    for seed in 0..200 {
+        let mut generator = SimulationRng::from_seed(seed);
+
+        let first = Genome::random(seed as usize % 12, &mut generator);
+        let second = Genome::random(seed as usize % 7, &mut generator);
+
+        assert_eq!(
+            first.distance(&second, &config).to_bits(),
+            second.distance(&first, &config).to_bits()
+        );
+    }
+}
+
+#[test]
+fn identical_genomes_are_no_distance_apart() {
+    let config = DistanceConfig::default();
+    let empty = Genome::new(Vec::new());
+
+    assert_eq!(empty.distance(&empty, &config), 0.0);
+
+    for seed in 0..50 {
+        let genome = Genome::random(10, &mut SimulationRng::from_seed(seed));
+
+        assert_eq!(genome.distance(&genome.clone(), &config), 0.0);
+    }
+
+    // A NaN weight or bias is compared bit for bit, so a copy of it is no different.
+    let genome = Genome::random(1, &mut SimulationRng::from_seed(0));
+    let nan = Genome::new(vec![
+        genome.genes()[0].with_weight(f32::NAN).with_bias(f32::NAN),
+    ]);
+
+    assert_eq!(nan.distance(&nan.clone(), &config), 0.0);
+}
+
+#[test]
+fn each_unmatched_gene_costs_the_length_coefficient() {
+    let config = DistanceConfig {
+        length_coefficient: 3.0,
+        ..Default::default()
+    };
+
+    let genome = Genome::random(4, &mut SimulationRng::from_seed(1));
+    let shorter = Genome::new(genome.genes()[..3].to_vec());
+    let empty = Genome::new(Vec::new());
+
+    // The cost is shared between every position in the longer genome.
+    assert_eq!(genome.distance(&shorter, &config), 3.0 / 4.0);
+    assert_eq!(genome.distance(&empty, &config), 3.0);
+}
+
+#[test]
+fn weight_differences_are_capped() {
+    let config = DistanceConfig::default();
+
+    let genome = Genome::random(1, &mut SimulationRng::from_seed(2));
+    let gene = &genome.genes()[0];
+
+    for weight in [1e9, f32::INFINITY, f32::NAN] {
+        let other = Genome::new(vec![gene.with_weight(weight)]);
+
+        assert!(genome.distance(&other, &config) <= 8.0 * config.weight_coefficient);
+    }
+}
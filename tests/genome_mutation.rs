@@ -0,0 +1,181 @@
+use proptest::prelude::*;
+
+use evolut::{
+    model::creature::genome::{
+        GaussianCreep, Gene, Genome, IdMutation, MutationModel, StructuralMutationConfig,
+    },
+    simulation::SimulationRng,
+};
+
+/// Structural mutations with any probabilities, and any bounds which allow at least one length.
+fn any_structural_config() -> impl Strategy<Value = StructuralMutationConfig> {
+    (
+        [0.0..=1.0f64, 0.0..=1.0, 0.0..=1.0, 0.0..=1.0],
+        0..20usize,
+        0..20usize,
+    )
+        .prop_map(|(probabilities, min_length, extra_length)| {
+            let [insertion, deletion, duplication, swap] = probabilities;
+
+            StructuralMutationConfig {
+                insertion_probability: insertion,
+                deletion_probability: deletion,
+                duplication_probability: duplication,
+                swap_probability: swap,
+                min_length,
+                max_length: min_length + extra_length,
+            }
+        })
+}
+
+proptest! {
+    #[test]
+    fn structural_mutations_keep_genomes_within_their_length_bounds(
+        config in any_structural_config(),
+        start in 0.0..=1.0f64,
+        seed in any::<u64>(),
+    ) {
+        let mut generator = SimulationRng::from_seed(seed);
+
+        let length = config.min_length
+            + ((config.max_length - config.min_length) as f64 * start).round() as usize;
+        let mut genome = Genome::random(length, &mut generator);
+
+        for _ in 0..100 {
+            genome = genome.mutated_structurally(&config, &mut generator);
+
+            prop_assert!(genome.genes().len() >= config.min_length);
+            prop_assert!(genome.genes().len() <= config.max_length);
+        }
+    }
+
+    #[test]
+    fn structural_mutations_never_move_genomes_further_out_of_bounds(
+        config in any_structural_config(),
+        length in 0..40usize,
+        seed in any::<u64>(),
+    ) {
+        let mut generator = SimulationRng::from_seed(seed);
+        let mut genome = Genome::random(length, &mut generator);
+
+        for _ in 0..100 {
+            let previous = genome.genes().len();
+            genome = genome.mutated_structurally(&config, &mut generator);
+
+            prop_assert!(genome.genes().len() >= previous.min(config.min_length));
+            prop_assert!(genome.genes().len() <= previous.max(config.max_length));
+        }
+    }
+}
+
+#[test]
+fn genomes_keep_their_length_when_the_bounds_are_equal() {
+    let config = StructuralMutationConfig {
+        insertion_probability: 1.0,
+        deletion_probability: 1.0,
+        duplication_probability: 1.0,
+        swap_probability: 1.0,
+        min_length: 5,
+        max_length: 5,
+    };
+
+    let mut generator = SimulationRng::from_seed(0);
+    let mut genome = Genome::random(5, &mut generator);
+
+    for _ in 0..1000 {
+        genome = genome.mutated_structurally(&config, &mut generator);
+
+        assert_eq!(genome.genes().len(), 5);
+    }
+}
+
+/// Creep which perturbs every weight and bias by a lot, and never touches IDs.
+fn strong_creep(weight_probability: f64) -> GaussianCreep {
+    GaussianCreep {
+        weight_probability,
+        sigma: 10.0,
+        min_weight: -4.0,
+        max_weight: 4.0,
+        ids: IdMutation::Resample,
+        id_rate: 0.0,
+    }
+}
+
+fn gene(weight: f32, bias: f32) -> Gene {
+    Gene::new(1, 130, weight, bias, 0)
+}
+
+#[test]
+fn creep_keeps_weights_and_biases_within_their_bounds() {
+    let model = strong_creep(1.0);
+    let mut generator = SimulationRng::from_seed(0);
+
+    let mut reached_bound = false;
+
+    for value in [-4.0, -3.9, 0.0, 3.9, 4.0] {
+        for _ in 0..200 {
+            let mutated = model.mutate(&gene(value, -value), &mut generator);
+
+            for value in [mutated.weight(), mutated.bias()] {
+                assert!((-4.0..=4.0).contains(&value));
+
+                reached_bound |= value.abs() == 4.0;
+            }
+        }
+    }
+
+    // Values pushed past a bound are clamped to it, rather than resampled.
+    assert!(reached_bound);
+}
+
+#[test]
+fn creep_brings_values_from_outside_the_bounds_back_into_range() {
+    for weight_probability in [0.0, 1.0] {
+        let model = strong_creep(weight_probability);
+        let mut generator = SimulationRng::from_seed(0);
+
+        let mutated = model.mutate(&gene(100.0, -100.0), &mut generator);
+        assert_eq!((mutated.weight(), mutated.bias()), (4.0, -4.0));
+
+        let mutated = model.mutate(&gene(f32::INFINITY, f32::NEG_INFINITY), &mut generator);
+        assert_eq!((mutated.weight(), mutated.bias()), (4.0, -4.0));
+
+        let mutated = model.mutate(&gene(f32::NAN, f32::NAN), &mut generator);
+        assert_eq!((mutated.weight(), mutated.bias()), (0.0, 0.0));
+    }
+}
+
+#[test]
+fn creep_without_perturbations_leaves_genes_alone() {
+    let model = strong_creep(0.0);
+    let mut generator = SimulationRng::from_seed(0);
+
+    let original = gene(1.5, -2.5);
+    let mutated = model.mutate(&original, &mut generator);
+
+    assert_eq!(mutated.as_hex(), original.as_hex());
+}
+
+#[test]
+fn creep_is_reproducible() {
+    let model = GaussianCreep {
+        weight_probability: 0.5,
+        id_rate: 0.5,
+        ..Default::default()
+    };
+
+    let mutate = |seed| {
+        let mut generator = SimulationRng::from_seed(seed);
+        let genome = Genome::random(50, &mut generator);
+
+        genome
+            .mutated(&model, &mut generator)
+            .genes()
+            .iter()
+            .map(|gene| gene.as_hex())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(mutate(11), mutate(11));
+    assert_ne!(mutate(11), mutate(12));
+}
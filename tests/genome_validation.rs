@@ -0,0 +1,172 @@
+use proptest::prelude::*;
+
+use evolut::model::creature::{
+    brain::{BrainConfig, CycleHandling, NeuronRegistry},
+    genome::{Gene, GeneProblem, Genome},
+};
+
+/// A sensory neuron, two internal neurons and an action neuron.
+const SENSOR: u8 = 0;
+const INTERNAL: u8 = 128;
+const OTHER_INTERNAL: u8 = 129;
+const ACTION: u8 = 0;
+
+fn gene(source_id: u8, destination_id: u8) -> Gene {
+    Gene::new(source_id, destination_id, 1.0, 0.5, 0)
+}
+
+fn problems(genes: Vec<Gene>, cycles: CycleHandling) -> Vec<GeneProblem> {
+    let config = BrainConfig {
+        cycles,
+        ..Default::default()
+    };
+
+    match Genome::new(genes).validate(&config, &NeuronRegistry::builtin()) {
+        Ok(()) => Vec::new(),
+        Err(invalid) => invalid.problems().clone(),
+    }
+}
+
+#[test]
+fn genes_which_reach_an_action_neuron_are_valid() {
+    let genes = vec![gene(SENSOR, INTERNAL), gene(INTERNAL, ACTION)];
+
+    assert_eq!(problems(genes, CycleHandling::Discard), []);
+}
+
+#[test]
+fn non_finite_weights_are_corrupt() {
+    let genes = vec![Gene::new(SENSOR, ACTION, f32::NAN, 0.0, 0)];
+
+    let problems = problems(genes, CycleHandling::Discard);
+
+    assert!(matches!(
+        problems[..],
+        [GeneProblem::NonFiniteWeight { index: 0, weight }] if weight.is_nan()
+    ));
+    assert!(problems[0].is_corrupt());
+}
+
+#[test]
+fn non_finite_biases_are_corrupt() {
+    let genes = vec![Gene::new(SENSOR, ACTION, 0.0, f32::INFINITY, 0)];
+
+    let problems = problems(genes, CycleHandling::Discard);
+
+    assert_eq!(
+        problems,
+        [GeneProblem::NonFiniteBias {
+            index: 0,
+            bias: f32::INFINITY
+        }]
+    );
+    assert!(problems[0].is_corrupt());
+}
+
+#[test]
+fn self_connections_are_only_a_problem_when_cycles_are_discarded() {
+    let genes = vec![
+        gene(SENSOR, INTERNAL),
+        gene(INTERNAL, INTERNAL),
+        gene(INTERNAL, ACTION),
+    ];
+
+    let discarded = problems(genes.clone(), CycleHandling::Discard);
+
+    assert_eq!(discarded, [GeneProblem::SelfConnection { index: 1 }]);
+    assert!(!discarded[0].is_corrupt());
+
+    assert_eq!(problems(genes, CycleHandling::Recurrent), []);
+}
+
+#[test]
+fn genes_which_never_reach_an_action_neuron_are_unexpressed() {
+    let genes = vec![
+        gene(SENSOR, ACTION),
+        gene(SENSOR, INTERNAL),
+        gene(INTERNAL, OTHER_INTERNAL),
+    ];
+
+    let problems = problems(genes, CycleHandling::Discard);
+
+    assert_eq!(
+        problems,
+        [
+            GeneProblem::Unexpressed { index: 1 },
+            GeneProblem::Unexpressed { index: 2 },
+        ]
+    );
+    assert!(!problems[0].is_corrupt());
+}
+
+#[test]
+fn genes_from_internal_neurons_without_inputs_are_unexpressed() {
+    // Nothing leads into the other internal neuron, so it is left out of the brain, and so is everything it drives.
+    let genes = vec![
+        gene(SENSOR, ACTION),
+        gene(OTHER_INTERNAL, INTERNAL),
+        gene(INTERNAL, ACTION),
+    ];
+
+    assert_eq!(
+        problems(genes, CycleHandling::Discard),
+        [
+            GeneProblem::Unexpressed { index: 1 },
+            GeneProblem::Unexpressed { index: 2 },
+        ]
+    );
+}
+
+#[test]
+fn recurrent_connections_count_as_inputs() {
+    // The internal neuron's only input is its own previous activation, which its bias keeps from being zero.
+    let genes = vec![gene(INTERNAL, INTERNAL), gene(INTERNAL, ACTION)];
+
+    assert_eq!(
+        problems(genes.clone(), CycleHandling::Discard),
+        [
+            GeneProblem::SelfConnection { index: 0 },
+            GeneProblem::Unexpressed { index: 1 },
+        ]
+    );
+    assert_eq!(problems(genes, CycleHandling::Recurrent), []);
+}
+
+/// Any gene at all, including those with NaN, infinite and subnormal weights and biases.
+fn any_gene() -> impl Strategy<Value = Gene> {
+    (
+        any::<u8>(),
+        any::<u8>(),
+        any::<u32>(),
+        any::<u32>(),
+        any::<u8>(),
+    )
+        .prop_map(|(source_id, destination_id, weight, bias, activation_id)| {
+            Gene::new(
+                source_id,
+                destination_id,
+                f32::from_bits(weight),
+                f32::from_bits(bias),
+                activation_id,
+            )
+        })
+}
+
+proptest! {
+    #[test]
+    fn repaired_genomes_are_never_corrupt(
+        genes in prop::collection::vec(any_gene(), 0..32),
+        recurrent in any::<bool>(),
+    ) {
+        let config = BrainConfig {
+            cycles: if recurrent { CycleHandling::Recurrent } else { CycleHandling::Discard },
+            ..Default::default()
+        };
+
+        let repaired = Genome::new(genes).repaired();
+
+        if let Err(invalid) = repaired.validate(&config, &NeuronRegistry::builtin()) {
+            prop_assert!(!invalid.is_corrupt(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use evolut::simulation::{
+    CreatureConfig, CreatureId, CreaturePlugin, EnergyConfig, FoodConfig, HeadlessPlugin,
+    LineagePlugin, LineageTree, SimulationConfig, SimulationRng, Snapshot, SpatialIndexPlugin,
+};
+
+/// Writes a lineage log to a file of its own, and rebuilds the tree from it.
+fn load(name: &str, log: &str) -> LineageTree {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "evolut-lineage-test-{}-{name}.jsonl",
+        std::process::id()
+    ));
+
+    fs::write(&path, log).unwrap();
+    let tree = LineageTree::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    tree
+}
+
+/// Two creatures of generation zero, where the first has a child and a grandchild, and the second dies without any.
+const LOG: &str = r#"{"event":"birth","id":0,"parent":null,"generation":0,"time":0.0}
+{"event":"birth","id":1,"parent":null,"generation":0,"time":0.0}
+{"event":"birth","id":2,"parent":0,"generation":1,"time":1.0}
+{"event":"birth","id":3,"parent":2,"mate":1,"generation":2,"time":2.5}
+{"event":"death","id":1,"time":3.0}
+"#;
+
+/// The creatures from before a snapshot taken at 1.2 seconds, then a timeline which was abandoned when the run was
+/// killed, then the timeline of the resumed run, which reuses the ID of the abandoned birth.
+const RESUMED_LOG: &str = r#"{"event":"birth","id":0,"parent":null,"generation":0,"time":0.0}
+{"event":"birth","id":1,"parent":null,"generation":0,"time":0.0}
+{"event":"birth","id":2,"parent":0,"generation":1,"time":1.0}
+{"event":"death","id":1,"time":1.5}
+{"event":"birth","id":3,"parent":2,"generation":2,"time":2.0}
+{"event":"death","id":0,"time":2.5}
+{"event":"resume","time":1.2}
+{"event":"birth","id":3,"parent":1,"generation":1,"time":1.4}
+{"event":"death","id":2,"time":1.6}
+"#;
+
+#[test]
+fn trees_are_written_in_newick_format() {
+    let tree = load("newick", LOG);
+
+    assert_eq!(tree.to_newick(), "(((3:1.5)2:1)0:0,1:0);");
+    assert_eq!(tree.nodes()[&CreatureId(3)].mate, Some(CreatureId(1)));
+    assert_eq!(tree.nodes()[&CreatureId(1)].death_time, Some(3.0));
+
+    assert_eq!(tree.survivors_only().to_newick(), "(((3:1.5)2:1)0:0);");
+}
+
+#[test]
+fn trees_are_written_as_edge_lists() {
+    let tree = load("edges", LOG);
+
+    assert_eq!(tree.to_edge_list(), "parent,child\n0,2\n2,3\n");
+}
+
+#[test]
+fn abandoned_timelines_are_discarded_on_resume() {
+    let tree = load("resumed", RESUMED_LOG);
+
+    // The deaths after the snapshot never happened, and the abandoned creature 3 is replaced by the resumed one.
+    assert_eq!(tree.nodes().len(), 4);
+    assert_eq!(tree.nodes()[&CreatureId(0)].death_time, None);
+    assert_eq!(tree.nodes()[&CreatureId(1)].death_time, None);
+    assert_eq!(tree.nodes()[&CreatureId(2)].death_time, Some(1.6));
+    assert_eq!(tree.nodes()[&CreatureId(3)].parent, Some(CreatureId(1)));
+
+    assert_eq!(tree.to_newick(), "((2:1)0:0,(3:1.4)1:0);");
+    assert_eq!(tree.to_edge_list(), "parent,child\n0,2\n1,3\n");
+    assert_eq!(tree.survivors_only().to_newick(), "(0:0,(3:1.4)1:0);");
+}
+
+/// A tiny world where every creature has enough energy to reproduce on each of its first few ticks.
+fn build_app(path: PathBuf) -> App {
+    let config = SimulationConfig {
+        creatures: CreatureConfig {
+            generation_zero_size: 3,
+            initial_energy: 2000.0,
+            ..Default::default()
+        },
+        energy: EnergyConfig {
+            reproduction_threshold: 600.0,
+            reproduction_cost: 500.0,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 0,
+            growth_rate: 0.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(LineagePlugin { path });
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+#[test]
+fn restored_creatures_are_not_born_again() {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "evolut-lineage-test-{}-restored.jsonl",
+        std::process::id()
+    ));
+
+    // The first update runs no fixed timesteps, so this is a single tick.
+    let mut app = build_app(path.clone());
+    app.update();
+    app.update();
+
+    let snapshot = Snapshot::capture(app.world_mut());
+    drop(app);
+
+    let mut resumed = build_app(path.clone());
+    snapshot.restore(resumed.world_mut());
+
+    for _ in 0..3 {
+        resumed.update();
+    }
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let records: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let resume = records
+        .iter()
+        .position(|record| record["event"] == "resume")
+        .unwrap();
+
+    let mut born = HashSet::new();
+
+    for record in records.iter().filter(|record| record["event"] == "birth") {
+        assert!(born.insert(record["id"].as_u64().unwrap()), "{record}");
+    }
+
+    // Creatures born before the snapshot are logged once, before it, and those born after it are still logged.
+    assert!(resume > 3);
+    assert!(
+        records[resume + 1..]
+            .iter()
+            .any(|record| record["event"] == "birth")
+    );
+}
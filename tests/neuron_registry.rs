@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use evolut::{
+    model::creature::{
+        brain::{Brain, BrainConfig, InputNeuron, Neuron, NeuronRegistry, SensoryInputs},
+        genome::Genome,
+    },
+    simulation::{
+        CreaturePlugin, Energy, FoodPlugin, HeadlessPlugin, SimulationConfig, SimulationRng,
+        SpatialIndexPlugin, TimeConfig,
+        neurons::{Sensor, SensorPlugin},
+    },
+};
+
+/// A sense which a downstream crate might add.
+struct DoubleEnergySensor;
+
+impl Sensor for DoubleEnergySensor {
+    const NAME: &'static str = "DoubleEnergy";
+
+    type Data = &'static Energy;
+    type Param = ();
+
+    fn sense(energy: &Energy, _: &()) -> f32 {
+        energy.value * 2.0
+    }
+}
+
+fn build_app() -> App {
+    let config = SimulationConfig {
+        time: TimeConfig {
+            // Brains are updated on every timestep.
+            brain_update_frequency: 1000.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(SensorPlugin::<DoubleEnergySensor>::default());
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+#[test]
+fn registered_sensors_come_after_the_built_in_ones() {
+    let app = build_app();
+    let registry = app.world().resource::<NeuronRegistry>();
+    let builtin = NeuronRegistry::builtin();
+
+    assert_eq!(
+        registry.sensory_inputs().len(),
+        builtin.sensory_inputs().len() + 1
+    );
+    assert_eq!(
+        registry.sensory_inputs().last().unwrap().name(),
+        "DoubleEnergy"
+    );
+
+    // The source id is taken modulo the number of registered inputs, so id 23 now reaches the new one.
+    let genome: Genome = "genome:v2:17003f8000000000000000".parse().unwrap();
+    let brain = Brain::new(&genome, &BrainConfig::default(), registry);
+
+    let Some(Neuron::Action(action_neuron)) = brain
+        .neurons()
+        .iter()
+        .find(|neuron| matches!(neuron, Neuron::Action(_)))
+    else {
+        panic!("The brain has no action neuron.");
+    };
+
+    let InputNeuron::Sensory(sensory_neuron) = action_neuron.inputs()[0].input() else {
+        panic!("The action neuron is not connected to a sensory neuron.");
+    };
+
+    assert_eq!(sensory_neuron.input().name(), "DoubleEnergy");
+}
+
+#[test]
+fn registered_sensors_are_sensed() {
+    let mut app = build_app();
+
+    for _ in 0..2 {
+        app.update();
+    }
+
+    let input = *app
+        .world()
+        .resource::<NeuronRegistry>()
+        .sensory_inputs()
+        .last()
+        .unwrap();
+
+    let mut query = app.world_mut().query::<(&SensoryInputs, &Energy)>();
+
+    assert!(query.iter(app.world()).count() > 0);
+
+    for (sensory_inputs, energy) in query.iter(app.world()) {
+        // Energy is deducted after the creature senses it.
+        assert!(sensory_inputs.value(&input) >= energy.value * 2.0);
+    }
+}
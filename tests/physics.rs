@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use evolut::simulation::{
+    AngularVelocity, Contact, CreatureConfig, CreaturePlugin, FoodConfig, FoodPlugin,
+    HeadlessPlugin, PhysicsConfig, SimulationConfig, SimulationRng, SpatialIndexPlugin, Velocity,
+    WorldConfig,
+};
+
+/// Strong creatures with low top speeds, so that random brains soon reach them.
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        physics: PhysicsConfig {
+            thrust: 1000.0,
+            torque: 1000.0,
+            linear_drag: 0.0,
+            angular_drag: 0.0,
+            max_speed: 2.0,
+            max_angular_speed: 1.0,
+            ..Default::default()
+        },
+        creatures: CreatureConfig {
+            generation_zero_size: 200,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// A crowded world without food, so that creatures keep bumping into each other but are never born.
+fn crowded_config() -> SimulationConfig {
+    SimulationConfig {
+        world: WorldConfig {
+            bounds: 20.0,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 0,
+            replacement_interval: 1000.0,
+            ..Default::default()
+        },
+        creatures: CreatureConfig {
+            generation_zero_size: 50,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn build_app(config: SimulationConfig) -> App {
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin);
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+#[test]
+fn creatures_never_exceed_their_top_speeds() {
+    let mut app = build_app(config());
+
+    let mut fastest: f32 = 0.0;
+
+    for _ in 0..300 {
+        app.update();
+
+        let mut query = app.world_mut().query::<(&Velocity, &AngularVelocity)>();
+
+        for (velocity, angular_velocity) in query.iter(app.world()) {
+            assert!(velocity.value.length() <= 2.0 + 1e-4);
+            assert!(angular_velocity.value.abs() <= 1.0);
+
+            fastest = fastest.max(velocity.value.length());
+        }
+    }
+
+    // Otherwise, nothing was tested.
+    assert!(fastest > 1.9);
+}
+
+#[test]
+fn colliding_creatures_are_pushed_apart() {
+    let mut app = build_app(crowded_config());
+
+    let mut touched = false;
+
+    for tick in 0..300 {
+        app.update();
+
+        let mut query = app.world_mut().query::<(&Transform, &Contact)>();
+
+        // Generation zero is spawned at random, and the creatures which overlap most take a few timesteps to be pushed
+        // apart.
+        if tick < 3 {
+            continue;
+        }
+
+        let creatures: Vec<(Vec2, bool)> = query
+            .iter(app.world())
+            .map(|(transform, contact)| (transform.translation.truncate(), contact.value))
+            .collect();
+
+        for (index, (position, _)) in creatures.iter().enumerate() {
+            for (other, _) in &creatures[index + 1..] {
+                // Several collisions at once can leave a little overlap, which is resolved over the next timesteps.
+                assert!(position.distance(*other) > 1.9);
+            }
+        }
+
+        touched |= creatures.iter().any(|(_, contact)| *contact);
+    }
+
+    assert!(touched);
+}
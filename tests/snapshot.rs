@@ -0,0 +1,224 @@
+use bevy::prelude::*;
+use std::{fs, path::PathBuf};
+
+use evolut::{
+    model::creature::{
+        brain::{Brain, CycleHandling},
+        genome::Genome,
+    },
+    simulation::{
+        Age, Contact, CreatureConfig, CreaturePlugin, Energy, EnergyConfig, Food, FoodKind,
+        FoodKindConfig, FoodPatch, FoodPlugin, HeadlessPlugin, Lineage, SimulationConfig,
+        SimulationRng, Snapshot, SpatialIndexPlugin, SpeciationConfig, SpeciationPlugin, Species,
+        TimeConfig, WorldConfig,
+    },
+};
+
+/// The number of fixed timesteps which each run lasts.
+const TICKS: usize = 300;
+/// The fixed timestep after which the interrupted run is snapshotted.
+const SNAPSHOT_TICK: usize = 150;
+
+/// Everything about a creature which should survive a snapshot, with floats compared bit for bit.
+#[derive(Debug, PartialEq)]
+struct CreatureState {
+    id: u64,
+    transform: [u32; 7],
+    contact: bool,
+    energy: u32,
+    age: u32,
+    genome: Vec<String>,
+    species: Option<u64>,
+    brain_state: Vec<u32>,
+}
+
+/// A small world with recurrent brains, births, species and decaying food, so that every part of a snapshot matters.
+fn config() -> SimulationConfig {
+    let mut config = SimulationConfig {
+        world: WorldConfig {
+            bounds: 30.0,
+            ..Default::default()
+        },
+        time: TimeConfig {
+            brain_update_frequency: 100.0,
+            ..Default::default()
+        },
+        creatures: CreatureConfig {
+            generation_zero_size: 30,
+            initial_energy: 400.0,
+            ..Default::default()
+        },
+        energy: EnergyConfig {
+            reproduction_threshold: 500.0,
+            reproduction_cost: 250.0,
+            ..Default::default()
+        },
+        speciation: SpeciationConfig {
+            interval: 0.1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    config.brain.cycles = CycleHandling::Recurrent;
+    config.food.initial = 60;
+    config.food.patches.push(FoodPatch {
+        x: 10.0,
+        y: 10.0,
+        radius: 10.0,
+        growth_rate: 100.0,
+        capacity: 20,
+        kind: FoodKind::Fruit,
+    });
+    config.food.fruit = FoodKindConfig {
+        energy: 2000.0,
+        decay_rate: 1000.0,
+    };
+
+    config
+}
+
+fn build_app(rng: SimulationRng) -> App {
+    let mut app = App::new();
+
+    app.insert_resource(config())
+        .insert_resource(rng)
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(SpeciationPlugin);
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+/// The creatures, sorted by their IDs, as restored creatures are spawned in a different order.
+fn population(app: &mut App) -> Vec<CreatureState> {
+    let mut population: Vec<CreatureState> = app
+        .world_mut()
+        .query::<(
+            &Transform,
+            &Contact,
+            &Energy,
+            &Age,
+            &Genome,
+            &Lineage,
+            Option<&Species>,
+            &Brain,
+        )>()
+        .iter(app.world())
+        .map(
+            |(transform, contact, energy, age, genome, lineage, species, brain)| CreatureState {
+                id: lineage.id.0,
+                transform: [
+                    transform.translation.x.to_bits(),
+                    transform.translation.y.to_bits(),
+                    transform.translation.z.to_bits(),
+                    transform.rotation.x.to_bits(),
+                    transform.rotation.y.to_bits(),
+                    transform.rotation.z.to_bits(),
+                    transform.rotation.w.to_bits(),
+                ],
+                contact: contact.value,
+                energy: energy.value.to_bits(),
+                age: age.value.to_bits(),
+                genome: genome.genes().iter().map(|gene| gene.as_hex()).collect(),
+                species: species.map(|species| species.id.0),
+                brain_state: brain.state().iter().map(|value| value.to_bits()).collect(),
+            },
+        )
+        .collect();
+
+    population.sort_by_key(|creature| creature.id);
+
+    population
+}
+
+fn food(app: &mut App) -> Vec<(u32, u32, FoodKind, u32)> {
+    let mut food: Vec<(u32, u32, FoodKind, u32)> = app
+        .world_mut()
+        .query::<(&Transform, &Food)>()
+        .iter(app.world())
+        .map(|(transform, food)| {
+            (
+                transform.translation.x.to_bits(),
+                transform.translation.y.to_bits(),
+                food.kind,
+                food.nutrition.to_bits(),
+            )
+        })
+        .collect();
+
+    food.sort_by_key(|&(x, y, _, nutrition)| (x, y, nutrition));
+
+    food
+}
+
+fn word_position(app: &App) -> u128 {
+    app.world().resource::<SimulationRng>().word_position()
+}
+
+#[test]
+fn resumed_runs_continue_exactly_where_they_left_off() {
+    let mut uninterrupted = build_app(SimulationRng::from_seed(7));
+    let mut interrupted = build_app(SimulationRng::from_seed(7));
+
+    for _ in 0..SNAPSHOT_TICK {
+        uninterrupted.update();
+        interrupted.update();
+    }
+
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("evolut-snapshot-test-{}.ron", std::process::id()));
+
+    Snapshot::capture(interrupted.world_mut())
+        .save(&path)
+        .unwrap();
+    drop(interrupted);
+
+    let snapshot = Snapshot::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut resumed = build_app(snapshot.rng());
+    snapshot.restore(resumed.world_mut());
+
+    // The first update of an app runs no fixed timesteps, as the clock has only just started.
+    resumed.update();
+
+    assert_eq!(population(&mut resumed), population(&mut uninterrupted));
+    assert_eq!(food(&mut resumed), food(&mut uninterrupted));
+    assert_eq!(word_position(&resumed), word_position(&uninterrupted));
+
+    for tick in SNAPSHOT_TICK..TICKS {
+        uninterrupted.update();
+        resumed.update();
+
+        assert!(
+            population(&mut resumed) == population(&mut uninterrupted),
+            "The populations diverged at tick {tick}."
+        );
+        assert!(
+            food(&mut resumed) == food(&mut uninterrupted),
+            "The food diverged at tick {tick}."
+        );
+        assert_eq!(
+            word_position(&resumed),
+            word_position(&uninterrupted),
+            "The random number generators diverged at tick {tick}."
+        );
+    }
+
+    // Otherwise, much of the snapshot was never tested.
+    let population = population(&mut uninterrupted);
+
+    assert!(population.iter().any(|creature| creature.species.is_some()));
+    assert!(population.len() > config().creatures.generation_zero_size as usize);
+    assert!(
+        food(&mut uninterrupted)
+            .iter()
+            .any(|&(_, _, kind, _)| kind == FoodKind::Fruit)
+    );
+}
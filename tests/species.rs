@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use evolut::simulation::{
+    CreatureConfig, CreaturePlugin, EnergyConfig, HeadlessPlugin, Lineage, SimulationConfig,
+    SimulationRng, SpatialIndexPlugin, SpeciationConfig, SpeciationMethod, SpeciationPlugin,
+    Species, SpeciesRegistry,
+};
+
+/// A population which neither dies nor reproduces, clustered into species every few ticks.
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        creatures: CreatureConfig {
+            generation_zero_size: 40,
+            initial_energy: 1e6,
+            ..Default::default()
+        },
+        energy: EnergyConfig {
+            reproduction_threshold: 1e9,
+            ..Default::default()
+        },
+        speciation: SpeciationConfig {
+            interval: 0.005,
+            method: SpeciationMethod::KMedoids,
+            k: 4,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn build_app(seed: u64) -> App {
+    let mut app = App::new();
+
+    app.insert_resource(config())
+        .insert_resource(SimulationRng::from_seed(seed))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(SpeciationPlugin);
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+/// The species of each creature, sorted by the creatures' IDs.
+fn assignments(app: &mut App) -> Vec<(u64, Option<u64>)> {
+    let mut assignments: Vec<(u64, Option<u64>)> = app
+        .world_mut()
+        .query::<(&Lineage, Option<&Species>)>()
+        .iter(app.world())
+        .map(|(lineage, species)| (lineage.id.0, species.map(|species| species.id.0)))
+        .collect();
+
+    assignments.sort();
+
+    assignments
+}
+
+#[test]
+fn k_medoids_assignments_are_reproducible() {
+    let mut first = build_app(5);
+    let mut second = build_app(5);
+
+    for _ in 0..20 {
+        first.update();
+        second.update();
+    }
+
+    let assignments = assignments(&mut first);
+
+    assert!(assignments.iter().all(|(_, species)| species.is_some()));
+    assert_eq!(
+        first.world().resource::<SpeciesRegistry>().species().len(),
+        config().speciation.k
+    );
+    assert_eq!(assignments, self::assignments(&mut second));
+}
+
+#[test]
+fn k_medoids_assignments_are_stable_while_the_population_is_unchanged() {
+    let mut app = build_app(6);
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let clustered = assignments(&mut app);
+    assert!(clustered.iter().all(|(_, species)| species.is_some()));
+
+    // Each later clustering starts from the medoids of the last, so it should settle on the same species.
+    for _ in 0..30 {
+        app.update();
+
+        assert_eq!(assignments(&mut app), clustered);
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use std::{fs, path::PathBuf};
+
+use evolut::simulation::{
+    CreatureConfig, CreaturePlugin, FoodConfig, FoodPlugin, HeadlessPlugin, SimulationConfig,
+    SimulationRng, Snapshot, SpatialIndexPlugin, StatisticsFormat, StatisticsPlugin,
+};
+
+const CSV_HEADER: &str = "time,population,births,deaths,energy_mean,energy_min,energy_max,age_mean,age_min,age_max,food,genome_length_mean,brain_size_mean,species,species_births,species_extinctions";
+
+/// A path of its own for each test, with nothing at it.
+fn path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "evolut-statistics-test-{}-{name}",
+        std::process::id()
+    ));
+
+    let _ = fs::remove_file(&path);
+
+    path
+}
+
+/// A tiny world which is sampled every 10 ticks.
+fn build_app(path: PathBuf, format: StatisticsFormat) -> App {
+    let config = SimulationConfig {
+        creatures: CreatureConfig {
+            generation_zero_size: 5,
+            ..Default::default()
+        },
+        food: FoodConfig {
+            initial: 7,
+            growth_rate: 0.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut app = App::new();
+
+    app.insert_resource(config)
+        .insert_resource(SimulationRng::from_seed(0))
+        .add_plugins(HeadlessPlugin)
+        .add_plugins(CreaturePlugin)
+        .add_plugins(FoodPlugin)
+        .add_plugins(SpatialIndexPlugin)
+        .add_plugins(StatisticsPlugin {
+            path,
+            format,
+            interval: 0.01,
+        });
+
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+/// Runs an app for a number of fixed timesteps, after the first update, which runs none.
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..=ticks {
+        app.update();
+    }
+}
+
+#[test]
+fn csv_statistics_have_a_header_and_a_row_per_sample() {
+    let path = path("rows.csv");
+
+    run(&mut build_app(path.clone(), StatisticsFormat::Csv), 35);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+
+    assert_eq!(lines[0], CSV_HEADER);
+    assert_eq!(lines.len(), 4);
+
+    for (index, line) in lines[1..].iter().enumerate() {
+        let fields: Vec<&str> = line.split(',').collect();
+
+        assert_eq!(fields.len(), CSV_HEADER.split(',').count());
+        assert_eq!(fields[0].parse::<f64>().unwrap(), (index + 1) as f64 * 0.01);
+        assert_eq!(fields[1], "5");
+        assert_eq!(fields[10], "7");
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn json_lines_statistics_have_an_object_per_sample() {
+    let path = path("rows.jsonl");
+
+    run(
+        &mut build_app(path.clone(), StatisticsFormat::JsonLines),
+        20,
+    );
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let samples: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(samples.len(), 2);
+
+    for (index, sample) in samples.iter().enumerate() {
+        assert_eq!(sample["time"], (index + 1) as f64 * 0.01);
+        assert_eq!(sample["population"], 5);
+        assert_eq!(sample["food"], 7);
+        assert!(sample["energy_mean"].is_number());
+        assert_eq!(sample["species"], 0);
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+/// Snapshots a run after 20 ticks and lets it run on to 40, as if it were killed long after its last checkpoint, then
+/// resumes from the snapshot for another 20 ticks. Returns the time of each sample in the file.
+fn resume_after_running_on(path: PathBuf, format: StatisticsFormat) -> Vec<f64> {
+    let mut app = build_app(path.clone(), format);
+    run(&mut app, 20);
+
+    let snapshot = Snapshot::capture(app.world_mut());
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    drop(app);
+
+    let mut resumed = build_app(path.clone(), format);
+    snapshot.restore(resumed.world_mut());
+    run(&mut resumed, 20);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    match format {
+        StatisticsFormat::Csv => {
+            let mut lines = contents.lines();
+
+            assert_eq!(lines.next(), Some(CSV_HEADER));
+
+            lines
+                .map(|line| line.split(',').next().unwrap().parse().unwrap())
+                .collect()
+        }
+        StatisticsFormat::JsonLines => contents
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["time"]
+                    .as_f64()
+                    .unwrap()
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn resumed_runs_replace_the_samples_taken_after_the_snapshot() {
+    for (name, format) in [
+        ("resumed.csv", StatisticsFormat::Csv),
+        ("resumed.jsonl", StatisticsFormat::JsonLines),
+    ] {
+        let times = resume_after_running_on(path(name), format);
+
+        assert!(
+            times.windows(2).all(|pair| pair[0] < pair[1]),
+            "{format:?}: {times:?}"
+        );
+        assert_eq!(times, [0.01, 0.02, 0.03, 0.04], "{format:?}");
+    }
+}
+
+#[test]
+fn statistics_which_cannot_be_written_are_skipped() {
+    let path = path("missing").join("statistics.csv");
+
+    run(&mut build_app(path.clone(), StatisticsFormat::Csv), 20);
+
+    assert!(!path.exists());
+}
@@ -0,0 +1,94 @@
+use bevy::math::Vec2;
+
+use evolut::simulation::{RepulsiveBorder, SimulationConfig, Topology, WallCollision, Walls};
+
+const BOUNDS: f32 = 50.0;
+const DELTA: f32 = 0.001;
+
+fn confine(topology: Topology, position: Vec2, velocity: Vec2) -> (Vec2, Vec2) {
+    let (mut position, mut velocity) = (position, velocity);
+
+    topology.confine(BOUNDS, &mut position, &mut velocity, DELTA);
+
+    (position, velocity)
+}
+
+fn topologies() -> [Topology; 4] {
+    [
+        Topology::Walls(Walls {
+            collision: WallCollision::Reflect,
+        }),
+        Topology::Walls(Walls {
+            collision: WallCollision::Stop,
+        }),
+        Topology::Toroidal,
+        Topology::Repulsive(RepulsiveBorder { strength: 1.0 }),
+    ]
+}
+
+#[test]
+fn positions_within_the_world_are_left_alone() {
+    for topology in topologies() {
+        let (position, velocity) = (Vec2::new(-49.0, 12.0), Vec2::new(-3.0, 4.0));
+
+        assert_eq!(confine(topology, position, velocity), (position, velocity));
+    }
+}
+
+#[test]
+fn walls_reflect_or_stop_creatures() {
+    let reflect = Topology::Walls(Walls {
+        collision: WallCollision::Reflect,
+    });
+
+    assert_eq!(
+        confine(reflect, Vec2::new(51.0, -52.0), Vec2::new(2.0, -3.0)),
+        (Vec2::new(49.0, -48.0), Vec2::new(-2.0, 3.0))
+    );
+
+    let stop = Topology::Walls(Walls {
+        collision: WallCollision::Stop,
+    });
+
+    assert_eq!(
+        confine(stop, Vec2::new(51.0, 10.0), Vec2::new(2.0, -3.0)),
+        (Vec2::new(50.0, 10.0), Vec2::new(0.0, -3.0))
+    );
+}
+
+#[test]
+fn toroidal_worlds_wrap_around() {
+    let (position, velocity) = confine(
+        Topology::Toroidal,
+        Vec2::new(51.0, -52.0),
+        Vec2::new(2.0, -3.0),
+    );
+
+    assert!((position - Vec2::new(-49.0, 48.0)).length() < 1e-4);
+    assert_eq!(velocity, Vec2::new(2.0, -3.0));
+}
+
+#[test]
+fn repulsive_borders_push_creatures_back() {
+    let (position, velocity) = confine(
+        Topology::Repulsive(RepulsiveBorder { strength: 1000.0 }),
+        Vec2::new(52.0, -60.0),
+        Vec2::new(0.0, 0.0),
+    );
+
+    assert_eq!(position, Vec2::new(52.0, -60.0));
+    assert_eq!(velocity, Vec2::new(-2.0, 10.0));
+}
+
+#[test]
+fn topologies_can_be_read_back_from_snapshots() {
+    for topology in topologies() {
+        let mut config = SimulationConfig::default();
+        config.world.topology = topology;
+
+        let written = ron::to_string(&config).unwrap();
+        let read: SimulationConfig = ron::from_str(&written).unwrap();
+
+        assert_eq!(ron::to_string(&read).unwrap(), written);
+    }
+}